@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::player::Player;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum Class {
+    Warrior,
+    Mage,
+    Barbarian,
+}
+
+impl Class {
+    pub const ALL: [Class; 3] = [Class::Warrior, Class::Mage, Class::Barbarian];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Class::Warrior => "Warrior",
+            Class::Mage => "Mage",
+            Class::Barbarian => "Barbarian",
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ClassDef {
+    // Everything classes.json says about a class
+    pub class: Class,
+    pub description: String,
+    pub start: StartingKit,
+    pub growth: Growth,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct StartingKit {
+    // What a new character of this class begins with
+    pub hp: i32,
+    pub mp: i32,
+    pub strength: i32,
+    pub defense: i32,
+    pub agility: i32,
+    pub intelligence: i32,
+    pub gold: i32,
+    // Names from weapons.json and spells.json, and item ids with a count
+    pub weapon: Option<String>,
+    #[serde(default)]
+    pub spells: Vec<String>,
+    #[serde(default)]
+    pub items: BTreeMap<String, u32>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Growth {
+    // What every level up in this class adds
+    pub strength: i32,
+    pub defense: i32,
+    pub maxHp: i32,
+    pub maxMp: i32,
+    pub agility: i32,
+    pub intelligence: i32,
+}
+
+impl Growth {
+    // What the player's stats would become, e.g. "Strength 1 -> 2, Defense 2 -> 4, ..."
+    pub fn preview(&self, player: &Player) -> String {
+        format!("Strength {} -> {}, Defense {} -> {}, HP {} -> {}, MP {} -> {}, Agility {} -> {}, Intelligence {} -> {}",
+            player.strength, player.strength + self.strength,
+            player.defense, player.defense + self.defense,
+            player.maxHp, player.maxHp + self.maxHp,
+            player.maxMp, player.maxMp + self.maxMp,
+            player.agility, player.agility + self.agility,
+            player.intelligence, player.intelligence + self.intelligence)
+    }
+}
@@ -0,0 +1,867 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::content::Content;
+use crate::frontend::Frontend;
+use crate::game::Game;
+use crate::item::{ItemUse, Stat};
+use crate::player::{Player, Spell};
+use crate::skill::{self, Cooldowns, SkillEffect};
+use crate::status::{self, StatusApply, StatusKind, Statuses};
+
+#[derive(Serialize, Deserialize)]
+pub struct Enemy {
+    // Our enemy details
+    pub name: String,
+    pub hp: i32,
+    pub maxHp: i32,
+    pub attack: i32,
+    pub agility: i32,
+    pub expToGive: i32,
+    pub goldToGive: i32,
+    pub resistances: BTreeMap<Element, i32>,
+    pub immunities: Vec<StatusKind>,
+    // Statuses the enemy's attacks can inflict
+    pub onHit: Vec<StatusApply>,
+    pub statuses: Statuses,
+    pub actions: Vec<EnemyAction>,
+    // Indexes into actions of the once-only actions already taken
+    pub usedActions: Vec<usize>,
+    // The power of a heavy attack that lands on the enemy's next turn
+    pub windingUp: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum Action {
+    // Everything an enemy can do on its turn
+    Attack,
+    // Spends a turn winding up, then hits for powerPercent of a normal attack
+    HeavyAttack { powerPercent: i32 },
+    Heal(i32),
+    // Leaves the fight, taking its exp and gold with it
+    Flee,
+    // Brings another enemy, by name, into the fight
+    CallAlly(String),
+    ApplyStatus(StatusApply),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EnemyAction {
+    pub action: Action,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    // Only picked while the enemy is below this percent of its max hp
+    #[serde(default)]
+    pub hpBelow: Option<i32>,
+    // Only picked once per fight
+    #[serde(default)]
+    pub once: bool,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum Element {
+    // The school a spell belongs to
+    Fire,
+    Ice,
+    Lightning,
+}
+
+impl Element {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Element::Fire => "fire",
+            Element::Ice => "ice",
+            Element::Lightning => "lightning",
+        }
+    }
+}
+
+// Every hit deals at least this much, so no amount of defense makes a fighter untouchable
+pub const MIN_DAMAGE: i32 = 1;
+// Percent chance that a hit is critical
+pub const CRIT_CHANCE: u32 = 10;
+// Critical hits deal this many halves of the normal damage, so 3 is 150%
+pub const CRIT_HALVES: i32 = 3;
+
+#[derive(Debug, PartialEq)]
+pub enum FightOutcome {
+    // How a trip to look for a fight ended
+    NoFight,
+    Won,
+    Fled,
+    // Every enemy left without the player defeating any of them
+    EnemyFled,
+    Defeated,
+}
+
+// A fight never has more enemies than this at once, calls for help go unanswered past it
+pub const MAX_ENEMIES: usize = 4;
+
+// Defending adds this much defense against the next hit taken
+pub const DEFEND_DEFENSE: i32 = 5;
+
+// Charging gives up a turn for an attack at this percent of normal damage, so it has
+// to beat the two plain attacks it costs to be worth the risk
+pub const CHARGE_POWER_PERCENT: i32 = 300;
+
+// Every this many points of intelligence take 1 mp off the cost of a spell
+pub const INTELLIGENCE_PER_MP: i32 = 4;
+
+// How turn order is worked out:
+//
+//   delay   = TURN_DELAY / (BASE_SPEED + speed), at least 1, speed being agility minus any slow
+//   start   = a random point within the combatant's first delay (initiative)
+//   order   = whoever is ready soonest acts, then waits another delay
+//
+// Ties go to the player, then to the enemy that joined the fight first. A combatant
+// twice as fast as another acts about twice as often, so extra actions fall out of
+// the same rule.
+pub const BASE_SPEED: i32 = 10;
+pub const TURN_DELAY: i32 = 1000;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Side {
+    Player,
+    // The index of the enemy in the fight
+    Enemy(usize),
+}
+
+pub fn turn_delay(speed: i32) -> i32 {
+    (TURN_DELAY / (BASE_SPEED + speed).max(1)).max(1)
+}
+
+pub fn player_speed(player: &Player) -> i32 {
+    player.stat(Stat::Agility) - player.statuses.potency(StatusKind::Slow)
+}
+
+pub fn enemy_speed(enemy: &Enemy) -> i32 {
+    enemy.agility - enemy.statuses.potency(StatusKind::Slow)
+}
+
+#[derive(Clone)]
+pub struct TurnQueue {
+    // When the player and each enemy, in fight order, are next ready to act
+    playerReadyAt: i32,
+    enemyReadyAt: Vec<i32>,
+    // When the last turn was taken
+    now: i32,
+}
+
+impl TurnQueue {
+    pub fn new(rng: &mut impl Rng, playerSpeed: i32, enemySpeeds: &[i32]) -> Self {
+        TurnQueue {
+            playerReadyAt: rng.gen_range(0..turn_delay(playerSpeed)),
+            enemyReadyAt: enemySpeeds.iter().map(|speed| rng.gen_range(0..turn_delay(*speed))).collect(),
+            now: 0,
+        }
+    }
+
+    // An enemy joining mid-fight rolls its initiative from the current turn
+    pub fn join(&mut self, rng: &mut impl Rng, speed: i32) {
+        self.enemyReadyAt.push(self.now + rng.gen_range(0..turn_delay(speed)));
+    }
+
+    pub fn remove(&mut self, enemy: usize) {
+        self.enemyReadyAt.remove(enemy);
+    }
+
+    // Whose turn it is, speeds are passed in each time since statuses change them mid-fight
+    pub fn next(&mut self, playerSpeed: i32, enemySpeeds: &[i32]) -> Side {
+        match self.enemyReadyAt.iter().copied().enumerate().min_by_key(|(_, readyAt)| *readyAt) {
+            Some((i, readyAt)) if readyAt < self.playerReadyAt => {
+                self.now = readyAt;
+                self.enemyReadyAt[i] += turn_delay(enemySpeeds[i]);
+                Side::Enemy(i)
+            }
+            _ => {
+                self.now = self.playerReadyAt;
+                self.playerReadyAt += turn_delay(playerSpeed);
+                Side::Player
+            }
+        }
+    }
+
+    // The next few turns if nobody's speed changes
+    pub fn preview(&self, playerSpeed: i32, enemySpeeds: &[i32], turns: usize) -> Vec<Side> {
+        let mut queue = self.clone();
+        (0..turns).map(|_| queue.next(playerSpeed, enemySpeeds)).collect()
+    }
+}
+
+pub struct Hit {
+    pub damage: i32,
+    pub crit: bool,
+}
+
+// How all damage in a fight is worked out:
+//
+//   roll    = uniform between the attacker's min and max power (variance)
+//   damage  = max(MIN_DAMAGE, roll - defense)
+//   crit    = CRIT_CHANCE% of hits, damage * CRIT_HALVES / 2 after defense
+//
+// Power ranges with max below min are treated as min..=min, and negative
+// defense is treated as 0, so the roll can never be an empty range.
+pub fn resolve(rng: &mut impl Rng, minPower: i32, maxPower: i32, defense: i32) -> Hit {
+    let roll = rng.gen_range(minPower..=maxPower.max(minPower));
+    let mut damage = (roll - defense.max(0)).max(MIN_DAMAGE);
+    let crit = rng.gen_range(0..100) < CRIT_CHANCE;
+    if crit {
+        damage = damage * CRIT_HALVES / 2;
+    }
+    Hit { damage, crit }
+}
+
+// Being slowed takes its potency off an attacker's power
+pub fn player_attack(rng: &mut impl Rng, player: &Player) -> Hit {
+    let weapon = &player.equippedWeapon;
+    let power = player.stat(Stat::Strength) - player.statuses.potency(StatusKind::Slow);
+    resolve(rng, (weapon.minDamage + power).max(0), weapon.maxDamage + power, 0)
+}
+
+// A weapon attack, spending the player's charge if they have one
+fn charged_attack(ui: &mut dyn Frontend, rng: &mut impl Rng, player: &mut Player) -> Hit {
+    let mut hit = player_attack(rng, player);
+    if let Some(powerPercent) = player.charging.take() {
+        ui.show("You unleash your charged attack!");
+        hit.damage = hit.damage * powerPercent / 100;
+    }
+    hit
+}
+
+// Intelligence adds to spells the way strength adds to weapons
+pub fn spell_attack(rng: &mut impl Rng, spell: &Spell, player: &Player) -> Hit {
+    let damage = spell.damage + player.stat(Stat::Intelligence);
+    resolve(rng, damage, damage, 0)
+}
+
+// Scales a hit by the enemy's resistance to its element: 50 halves it, -50 adds half again
+// and 100 makes the enemy immune. Anything short of immunity still deals MIN_DAMAGE.
+pub fn apply_resistance(hit: Hit, resistance: i32) -> Hit {
+    let damage = hit.damage * (100 - resistance) / 100;
+    let damage = if resistance >= 100 { 0 } else { damage.max(MIN_DAMAGE) };
+    Hit { damage, crit: hit.crit }
+}
+
+// Intelligence makes spells cheaper, but a spell that costs mp always costs at least 1
+pub fn spell_cost(spell: &Spell, player: &Player) -> i32 {
+    if spell.mpCost == 0 {
+        return 0;
+    }
+    (spell.mpCost - player.stat(Stat::Intelligence).max(0) / INTELLIGENCE_PER_MP).max(1)
+}
+
+pub fn enemy_attack(rng: &mut impl Rng, enemy: &Enemy, player: &Player) -> Hit {
+    resolve(rng, 1, enemy.attack - enemy.statuses.potency(StatusKind::Slow), player.stat(Stat::Defense) + player.guard)
+}
+
+pub enum EnemyTurn {
+    // What came of the enemy's turn
+    Continue,
+    Defeated,
+    Fled,
+    CalledAlly(String),
+}
+
+// Picks among the actions whose rules allow them right now, weighted by their weight.
+// None means the enemy has nothing to pick from and falls back to a basic attack.
+pub fn choose_action(rng: &mut impl Rng, enemy: &Enemy) -> Option<usize> {
+    let allowed: Vec<usize> = enemy.actions.iter().enumerate()
+        .filter(|(i, a)| !(a.once && enemy.usedActions.contains(i)))
+        .filter(|(_, a)| a.hpBelow.is_none_or(|percent| enemy.hp * 100 < enemy.maxHp * percent))
+        .map(|(i, _)| i)
+        .collect();
+    let total: u32 = allowed.iter().map(|i| enemy.actions[*i].weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.gen_range(0..total);
+    for i in allowed {
+        if roll < enemy.actions[i].weight {
+            return Some(i);
+        }
+        roll -= enemy.actions[i].weight;
+    }
+    None
+}
+
+pub fn enemy_turn(ui: &mut dyn Frontend, rng: &mut impl Rng, enemy: &mut Enemy, player: &mut Player) -> EnemyTurn {
+    // A wound up heavy attack always lands on the turn after
+    if let Some(powerPercent) = enemy.windingUp.take() {
+        ui.show(&format!("The {} unleashes its heavy attack!", enemy.name));
+        return strike(ui, rng, enemy, player, powerPercent);
+    }
+    let action = match choose_action(rng, enemy) {
+        _ if enemy.statuses.has(StatusKind::Taunt) => Action::Attack,
+        Some(i) => {
+            if enemy.actions[i].once {
+                enemy.usedActions.push(i);
+            }
+            enemy.actions[i].action.clone()
+        }
+        None => Action::Attack,
+    };
+    match action {
+        Action::Attack => strike(ui, rng, enemy, player, 100),
+        Action::HeavyAttack { powerPercent } => {
+            ui.show(&format!("The {} is winding up a heavy attack!", enemy.name));
+            enemy.windingUp = Some(powerPercent);
+            EnemyTurn::Continue
+        }
+        Action::Heal(amount) => {
+            enemy.hp = (enemy.hp + amount).min(enemy.maxHp);
+            ui.show(&format!("The {} heals {} hp! The {} has {} hp remaining!", enemy.name, amount, enemy.name, enemy.hp));
+            EnemyTurn::Continue
+        }
+        Action::Flee => {
+            ui.show(&format!("The {} flees from the fight!", enemy.name));
+            EnemyTurn::Fled
+        }
+        Action::CallAlly(name) => {
+            ui.show(&format!("The {} calls for help!", enemy.name));
+            EnemyTurn::CalledAlly(name)
+        }
+        Action::ApplyStatus(apply) => {
+            ui.show(&format!("The {} tries to leave you {}!", enemy.name, apply.kind.name()));
+            status::inflict(ui, rng, &player.name, &mut player.statuses, &apply, &[]);
+            EnemyTurn::Continue
+        }
+    }
+}
+
+// An attack at powerPercent of the enemy's normal damage
+fn strike(ui: &mut dyn Frontend, rng: &mut impl Rng, enemy: &Enemy, player: &mut Player, powerPercent: i32) -> EnemyTurn {
+    let mut hit = enemy_attack(rng, enemy, player);
+    hit.damage = (hit.damage * powerPercent / 100).max(MIN_DAMAGE);
+    player.guard = 0;
+    if hit.crit {
+        ui.show(&format!("The {} lands a critical hit!", enemy.name));
+    }
+    player.take_Damage(ui, hit.damage);
+    if player.hp <= 0 {
+        ui.show(&format!("You have been defeated by the {}!", enemy.name));
+        return EnemyTurn::Defeated;
+    }
+    ui.show(&format!("The {} has dealt {} damage to you! You have {} hp remaining!", enemy.name, hit.damage, player.hp));
+    for apply in &enemy.onHit {
+        status::inflict(ui, rng, &player.name, &mut player.statuses, apply, &[]);
+    }
+    EnemyTurn::Continue
+}
+
+// The whole group's exp and gold are handed out together once the fight is won
+fn reward(ui: &mut dyn Frontend, player: &mut Player, defeated: &[Enemy]) {
+    let exp = defeated.iter().map(|e| e.expToGive).sum();
+    let gold = defeated.iter().map(|e| e.goldToGive).sum();
+    let names: Vec<&str> = defeated.iter().map(|e| e.name.as_str()).collect();
+    ui.clear();
+    ui.show(&format!("You have defeated the {}! You have gained {} exp!", names.join(", the "), exp));
+    player.gain_Exp(ui, exp);
+    player.gain_Gold(ui, gold);
+}
+
+// Returns whether the ally made it into the fight
+fn call_ally(ui: &mut dyn Frontend, rng: &mut impl Rng, content: &Content, name: &str, enemies: &mut Vec<Enemy>, queue: &mut TurnQueue) -> bool {
+    let Some(template) = content.enemy(name) else {
+        return false;
+    };
+    if enemies.len() >= MAX_ENEMIES {
+        ui.show("No one answers the call!");
+        return false;
+    }
+    let ally = template.spawn(rng);
+    ui.show(&format!("A {} joins the fight with {} hp!", ally.name, ally.hp));
+    queue.join(rng, enemy_speed(&ally));
+    enemies.push(ally);
+    true
+}
+
+// Which enemy the player means, only asked when there is a choice. None goes back to the action menu.
+fn choose_target(ui: &mut dyn Frontend, enemies: &[Enemy]) -> Option<usize> {
+    if enemies.len() == 1 {
+        return Some(0);
+    }
+    let options: Vec<String> = enemies.iter().enumerate()
+        .map(|(i, e)| format!("> {} - {} with {} hp{}", i + 1, e.name, e.hp, e.statuses.describe()))
+        .collect();
+    let input = ui.menu("Which enemy?", &options);
+    match input.parse::<usize>() {
+        Ok(n) if (1..=enemies.len()).contains(&n) => Some(n - 1),
+        _ => {
+            if !matches!(input.as_str(), "quit" | "Quit" | "back" | "Back") {
+                ui.show("Invalid target!");
+            }
+            None
+        }
+    }
+}
+
+fn land(ui: &mut dyn Frontend, hit: Hit, enemy: &mut Enemy) {
+    if hit.crit {
+        ui.show("A critical hit!");
+    }
+    enemy.hp -= hit.damage;
+    ui.show(&format!("You have dealt {} damage to the {}! The {} has {} hp remaining!", hit.damage, enemy.name, enemy.name, enemy.hp));
+}
+
+fn cast(ui: &mut dyn Frontend, rng: &mut impl Rng, spell: &Spell, player: &Player, enemy: &mut Enemy) {
+    let mut hit = spell_attack(rng, spell, player);
+    if hit.crit {
+        ui.show("A critical hit!");
+    }
+    if let Some(element) = spell.element {
+        let resistance = enemy.resistances.get(&element).copied().unwrap_or(0);
+        hit = apply_resistance(hit, resistance);
+        match resistance {
+            100.. => ui.show(&format!("The {} is immune to {}!", enemy.name, element.name())),
+            1..=99 => ui.show(&format!("The {} resists {}. It's not very effective...", enemy.name, element.name())),
+            ..=-1 => ui.show(&format!("The {} is weak to {}. It's super effective!", enemy.name, element.name())),
+            0 => (),
+        }
+    }
+    enemy.hp -= hit.damage;
+    ui.show(&format!("You have cast {} on the {}! The {} has {} hp remaining!", spell.name, enemy.name, enemy.name, enemy.hp));
+    if enemy.hp > 0 {
+        for apply in &spell.statuses {
+            status::inflict(ui, rng, &format!("The {}", enemy.name), &mut enemy.statuses, apply, &enemy.immunities);
+        }
+    }
+}
+
+pub fn look_For_Fight2(ui: &mut dyn Frontend, player: &mut Player, content: &Content, game: &mut Game) -> FightOutcome {
+    ui.clear();
+    let mut options: Vec<String> = content.locations.iter()
+        .map(|l| format!("> {} - {}", l.name, l.description))
+        .collect();
+    options.push("Back - Return to the main menu.".to_string());
+    let input = ui.menu("Where would you like to go?", &options);
+    let input = input.as_str();
+    if matches!(input, "quit" | "Quit" | "back" | "Back") {
+        return FightOutcome::NoFight;
+    }
+    let location = match content.locations.iter().find(|l| l.name.eq_ignore_ascii_case(input)) {
+        Some(location) => location,
+        None => {
+            ui.show("Invalid location!");
+            return FightOutcome::NoFight;
+        }
+    };
+    // Validation guarantees every location has encounters, each naming at least one known enemy
+    let rng = &mut game.rng;
+    let encounter = &location.enemies[rng.gen_range(0..location.enemies.len())];
+    let mut enemies: Vec<Enemy> = encounter.names().iter()
+        .map(|name| content.enemy(name).expect("location refers to a validated enemy").spawn(rng))
+        .collect();
+    for enemy in &enemies {
+        ui.show(&format!("You have encountered a {} with {} hp!", enemy.name, enemy.hp));
+    }
+
+    let speeds = |enemies: &[Enemy]| enemies.iter().map(enemy_speed).collect::<Vec<i32>>();
+    let mut queue = TurnQueue::new(rng, player_speed(player), &speeds(&enemies));
+    // Enemies drop out of the fight as they are defeated, and are rewarded for together at the end
+    let mut defeated: Vec<Enemy> = Vec::new();
+    let mut cooldowns = Cooldowns::default();
+    let outcome = 'fight: loop {
+        match queue.next(player_speed(player), &speeds(&enemies)) {
+            Side::Enemy(i) => {
+                let mut fled = false;
+                if let Some(kind) = enemies[i].statuses.skips_turn() {
+                    ui.show(&format!("The {} is {} and can't act!", enemies[i].name, kind.name()));
+                } else {
+                    match enemy_turn(ui, rng, &mut enemies[i], player) {
+                        EnemyTurn::Continue => (),
+                        EnemyTurn::Defeated => break FightOutcome::Defeated,
+                        EnemyTurn::Fled => fled = true,
+                        EnemyTurn::CalledAlly(name) => {
+                            call_ally(ui, rng, content, &name, &mut enemies, &mut queue);
+                        }
+                    }
+                }
+                if fled {
+                    enemies.remove(i);
+                    queue.remove(i);
+                } else {
+                    let enemy = &mut enemies[i];
+                    status::tick(ui, &format!("The {}", enemy.name), &mut enemy.hp, enemy.maxHp, &mut enemy.statuses);
+                }
+            }
+            Side::Player => {
+                player.guard = 0;
+                cooldowns.tick();
+                let foes: Vec<String> = enemies.iter().map(|e| format!(" | {}: {} hp{}", e.name, e.hp, e.statuses.describe())).collect();
+                let charged = if player.charging.is_some() { " [charged]" } else { "" };
+                ui.show(&format!("{}: {}/{} hp{}{}{}", player.name, player.hp, player.maxHp, charged, player.statuses.describe(), foes.concat()));
+                let order: Vec<&str> = queue.preview(player_speed(player), &speeds(&enemies), 4).into_iter()
+                    .map(|side| match side {
+                        Side::Player => "You",
+                        Side::Enemy(i) => enemies[i].name.as_str(),
+                    })
+                    .collect();
+                ui.show(&format!("Next up: You, {}", order.join(", ")));
+                if let Some(kind) = player.statuses.skips_turn() {
+                    ui.show(&format!("You are {} and can't act!", kind.name()));
+                } else {
+                    // Invalid choices ask again without using up the turn
+                    loop {
+                        ui.show("What would you like to do?");
+                        ui.show("attack");
+                        ui.show("defend");
+                        ui.show("charge");
+                        if !skill::known(content, player).is_empty() {
+                            ui.show("skill");
+                        }
+                        ui.show("magic");
+                        ui.show("item");
+                        ui.show("run");
+                        match ui.prompt("").as_str() {
+                            "attack" => {
+                                let Some(target) = choose_target(ui, &enemies) else { continue };
+                                let hit = charged_attack(ui, rng, player);
+                                land(ui, hit, &mut enemies[target]);
+                            }
+                            "defend" | "Defend" => {
+                                player.guard = DEFEND_DEFENSE;
+                                ui.show("You raise your guard against the next hit!");
+                            }
+                            "charge" | "Charge" => {
+                                player.charging = Some(CHARGE_POWER_PERCENT);
+                                ui.show("You gather your strength for the next attack!");
+                            }
+                            "skill" | "Skill" => {
+                                let skills = skill::known(content, player);
+                                if skills.is_empty() {
+                                    ui.show("You do not know any skills!");
+                                    continue;
+                                }
+                                skill::show_skills(ui, &skills, &cooldowns);
+                                let skillName = ui.prompt("Enter the name of the skill you would like to use:");
+                                if matches!(skillName.as_str(), "quit" | "Quit" | "back" | "Back") {
+                                    continue;
+                                }
+                                let Some(skill) = skills.into_iter().find(|s| s.name.eq_ignore_ascii_case(&skillName)) else {
+                                    ui.show(&format!("You do not know {}!", skillName));
+                                    continue;
+                                };
+                                if cooldowns.remaining(skill) > 0 {
+                                    ui.show(&format!("{} is not ready yet!", skill.name));
+                                    continue;
+                                }
+                                match skill.effect {
+                                    SkillEffect::Strike { hits, powerPercent } => {
+                                        let Some(target) = choose_target(ui, &enemies) else { continue };
+                                        ui.show(&format!("You use {}!", skill.name));
+                                        for _ in 0..hits {
+                                            if enemies[target].hp <= 0 {
+                                                break;
+                                            }
+                                            let mut hit = charged_attack(ui, rng, player);
+                                            hit.damage = (hit.damage * powerPercent / 100).max(MIN_DAMAGE);
+                                            land(ui, hit, &mut enemies[target]);
+                                        }
+                                    }
+                                    SkillEffect::Taunt { turns } => {
+                                        ui.show(&format!("You use {}!", skill.name));
+                                        let apply = StatusApply { kind: StatusKind::Taunt, turns, potency: 0, chance: 100 };
+                                        for enemy in &mut enemies {
+                                            status::inflict(ui, rng, &format!("The {}", enemy.name), &mut enemy.statuses, &apply, &enemy.immunities);
+                                        }
+                                    }
+                                }
+                                cooldowns.start(skill);
+                            }
+                            "magic" | "Magic" => {
+                                player.show_spellList(ui, content);
+                                let spellName = ui.prompt("Enter the name of the spell you would like to use:");
+                                let spellName = spellName.as_str();
+                                if matches!(spellName, "quit" | "Quit" | "back" | "Back") {
+                                    continue;
+                                }
+                                // Any spell the player has bought can be cast, so new spells only need to be sold
+                                // The content's version of a spell wins over the copy in the save, so rebalancing reaches old characters
+                                let spell = match player.spellInventory.iter().find(|s| s.name.eq_ignore_ascii_case(spellName)) {
+                                    Some(spell) => content.spell(&spell.name).unwrap_or(spell).clone(),
+                                    None => {
+                                        ui.show(&format!("You do not have {} in your spell list!", spellName));
+                                        continue;
+                                    }
+                                };
+                                let cost = spell_cost(&spell, player);
+                                if player.mp < cost {
+                                    ui.show(&format!("You do not have enough mp to cast {}!", spell.name));
+                                    continue;
+                                }
+                                let targets: Vec<usize> = if spell.area {
+                                    (0..enemies.len()).collect()
+                                } else {
+                                    match choose_target(ui, &enemies) {
+                                        Some(target) => vec![target],
+                                        None => continue,
+                                    }
+                                };
+                                player.remove_Mp(ui, cost);
+                                for target in targets {
+                                    cast(ui, rng, &spell, player, &mut enemies[target]);
+                                }
+                            }
+                            "item" | "Item" => {
+                                player.show_inventory(ui, content);
+                                let item = ui.prompt("Enter the name of the item you would like to use:");
+                                match player.use_item(ui, &item, content, true) {
+                                    ItemUse::NotUsed => continue,
+                                    ItemUse::Escaped => {
+                                        ui.show("You have escaped from the fight!");
+                                        break 'fight FightOutcome::Fled;
+                                    }
+                                    ItemUse::Used => (),
+                                }
+                            }
+                            "run" => {
+                                ui.clear();
+                                ui.show("You have run away from the fight!");
+                                break 'fight FightOutcome::Fled;
+                            }
+                            "quit" => break 'fight FightOutcome::Fled,
+                            _ => {
+                                ui.show("Invalid command!");
+                                continue;
+                            }
+                        }
+                        break;
+                    }
+                }
+                status::tick(ui, &player.name, &mut player.hp, player.maxHp, &mut player.statuses);
+                if player.hp <= 0 {
+                    let names: Vec<&str> = enemies.iter().map(|e| e.name.as_str()).collect();
+                    ui.show(&format!("You have succumbed while fighting the {}!", names.join(", the ")));
+                    break FightOutcome::Defeated;
+                }
+                player.tick_buffs(ui);
+            }
+        }
+
+        let mut i = 0;
+        while i < enemies.len() {
+            if enemies[i].hp > 0 {
+                i += 1;
+                continue;
+            }
+            let enemy = enemies.remove(i);
+            queue.remove(i);
+            ui.show(&format!("The {} has been defeated!", enemy.name));
+            defeated.push(enemy);
+        }
+        if enemies.is_empty() {
+            if defeated.is_empty() {
+                break FightOutcome::EnemyFled;
+            }
+            reward(ui, player, &defeated);
+            break FightOutcome::Won;
+        }
+    };
+    player.buffs.clear();
+    player.statuses.clear();
+    player.guard = 0;
+    player.charging = None;
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::frontend::ScriptedFrontend;
+
+    #[test]
+    fn defense_above_attack_deals_min_damage() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let hit = resolve(&mut rng, 1, 5, 50);
+            assert!(hit.damage == MIN_DAMAGE || hit.damage == MIN_DAMAGE * CRIT_HALVES / 2);
+        }
+    }
+
+    #[test]
+    fn defense_equal_to_attack_does_not_panic() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..1000 {
+            assert!(resolve(&mut rng, 1, 10, 10).damage >= MIN_DAMAGE);
+        }
+    }
+
+    #[test]
+    fn zero_attack_and_inverted_ranges_do_not_panic() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(resolve(&mut rng, 1, 0, 0).damage >= MIN_DAMAGE);
+        assert!(resolve(&mut rng, 8, 3, 0).damage >= 8);
+        assert!(resolve(&mut rng, 1, 5, -4).damage <= 5 * CRIT_HALVES / 2);
+    }
+
+    #[test]
+    fn damage_stays_within_the_documented_bounds() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut crits = 0;
+        for _ in 0..10_000 {
+            let hit = resolve(&mut rng, 5, 10, 2);
+            if hit.crit {
+                crits += 1;
+                assert!((4..=12).contains(&hit.damage));
+            } else {
+                assert!((3..=8).contains(&hit.damage));
+            }
+        }
+        // Roughly CRIT_CHANCE percent of 10000 hits
+        assert!((700..1300).contains(&crits));
+    }
+
+    #[test]
+    fn intelligence_raises_spell_damage_and_lowers_its_cost() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let spell = Spell { name: "Test".to_string(), description: String::new(), mpCost: 5, damage: 10, element: None, statuses: Vec::new(), area: false, classes: Vec::new() };
+        let mut player = Player::new("Tester".to_string());
+        assert_eq!(spell_cost(&spell, &player), 5);
+        player.intelligence = 8;
+        assert_eq!(spell_cost(&spell, &player), 3);
+        player.intelligence = 100;
+        assert_eq!(spell_cost(&spell, &player), 1);
+        player.intelligence = 6;
+        for _ in 0..100 {
+            let hit = spell_attack(&mut rng, &spell, &player);
+            assert!(hit.damage == 16 || hit.damage == 16 * CRIT_HALVES / 2);
+        }
+    }
+
+    #[test]
+    fn resistances_scale_damage_but_only_immunity_stops_it() {
+        let hit = |damage| Hit { damage, crit: false };
+        assert_eq!(apply_resistance(hit(10), 0).damage, 10);
+        assert_eq!(apply_resistance(hit(10), 50).damage, 5);
+        assert_eq!(apply_resistance(hit(10), -50).damage, 15);
+        assert_eq!(apply_resistance(hit(1), 90).damage, MIN_DAMAGE);
+        assert_eq!(apply_resistance(hit(10), 100).damage, 0);
+    }
+
+    #[test]
+    fn faster_combatants_act_more_often() {
+        let mut queue = TurnQueue { playerReadyAt: 0, enemyReadyAt: vec![0], now: 0 };
+        let turns: Vec<Side> = (0..30).map(|_| queue.next(0, &[10])).collect();
+        let enemyTurns = turns.iter().filter(|s| **s == Side::Enemy(0)).count();
+        assert_eq!(turns[0], Side::Player);
+        assert_eq!(enemyTurns, 20);
+    }
+
+    #[test]
+    fn huge_speeds_still_take_turns_in_order() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut queue = TurnQueue::new(&mut rng, i32::MAX - BASE_SPEED, &[5000]);
+        queue.join(&mut rng, 991);
+        let turns: Vec<Side> = (0..6).map(|_| queue.next(i32::MAX - BASE_SPEED, &[5000, 991])).collect();
+        assert!(turns.contains(&Side::Player) && turns.contains(&Side::Enemy(0)) && turns.contains(&Side::Enemy(1)));
+    }
+
+    #[test]
+    fn preview_does_not_use_up_turns() {
+        let mut queue = TurnQueue { playerReadyAt: 5, enemyReadyAt: vec![0], now: 0 };
+        let preview = queue.preview(0, &[0], 3);
+        assert_eq!(preview, [Side::Enemy(0), Side::Player, Side::Enemy(0)]);
+        assert_eq!(queue.next(0, &[0]), Side::Enemy(0));
+    }
+
+    #[test]
+    fn every_enemy_in_a_group_gets_its_turns() {
+        let mut queue = TurnQueue { playerReadyAt: 0, enemyReadyAt: vec![0, 50], now: 0 };
+        let turns: Vec<Side> = (0..7).map(|_| queue.next(0, &[0, 10])).collect();
+        let expected = [Side::Player, Side::Enemy(0), Side::Enemy(1), Side::Player, Side::Enemy(0), Side::Enemy(1), Side::Enemy(1)];
+        assert_eq!(turns, expected);
+        queue.remove(0);
+        queue.join(&mut StdRng::seed_from_u64(8), 0);
+        assert_eq!(queue.enemyReadyAt.len(), 2);
+        assert!(queue.enemyReadyAt[1] >= queue.now);
+    }
+
+    fn enemy_with(actions: Vec<EnemyAction>) -> Enemy {
+        Enemy {
+            name: "Dummy".to_string(), hp: 100, maxHp: 100, attack: 5, agility: 0, expToGive: 0, goldToGive: 0,
+            resistances: BTreeMap::new(), immunities: Vec::new(), onHit: Vec::new(), statuses: Statuses::default(),
+            actions, usedActions: Vec::new(), windingUp: None,
+        }
+    }
+
+    #[test]
+    fn actions_follow_their_hp_and_once_rules() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let action = |action, weight, hpBelow, once| EnemyAction { action, weight, hpBelow, once };
+        let mut enemy = enemy_with(vec![
+            action(Action::Flee, 5, Some(40), false),
+            action(Action::CallAlly("Dummy".to_string()), 1, None, true),
+        ]);
+        for _ in 0..100 {
+            assert_eq!(choose_action(&mut rng, &enemy), Some(1));
+        }
+        enemy.usedActions.push(1);
+        assert_eq!(choose_action(&mut rng, &enemy), None);
+        enemy.hp = 39;
+        assert_eq!(choose_action(&mut rng, &enemy), Some(0));
+        assert_eq!(choose_action(&mut rng, &enemy_with(Vec::new())), None);
+    }
+
+    #[test]
+    fn calls_for_help_stop_once_the_fight_is_full() {
+        let content = Content::load(std::path::Path::new("content")).expect("bundled content is valid");
+        let mut rng = StdRng::seed_from_u64(10);
+        let mut ui = ScriptedFrontend::new(&[]);
+        let mut enemies = vec![enemy_with(Vec::new())];
+        let mut queue = TurnQueue::new(&mut rng, 0, &[0]);
+        // Every goblin that joins can call for help again, so only the cap ends the chain
+        let joined = (0..10).filter(|_| call_ally(&mut ui, &mut rng, &content, "Goblin", &mut enemies, &mut queue)).count();
+        assert_eq!(joined, MAX_ENEMIES - 1);
+        assert_eq!((enemies.len(), queue.enemyReadyAt.len()), (MAX_ENEMIES, MAX_ENEMIES));
+        assert!(ui.transcript().contains("No one answers the call!"));
+    }
+
+    #[test]
+    fn defending_blunts_only_the_next_hit() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut ui = ScriptedFrontend::new(&[]);
+        let enemy = enemy_with(Vec::new());
+        let mut player = Player::new("Tester".to_string());
+        player.guard = DEFEND_DEFENSE;
+        for _ in 0..100 {
+            assert!(enemy_attack(&mut rng, &enemy, &player).damage <= MIN_DAMAGE * CRIT_HALVES / 2);
+        }
+        strike(&mut ui, &mut rng, &enemy, &mut player, 100);
+        assert_eq!(player.guard, 0);
+    }
+
+    #[test]
+    fn charging_beats_attacking_twice() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut ui = ScriptedFrontend::new(&[]);
+        let mut player = Player::new("Tester".to_string());
+        let (mut charged, mut twice) = (0, 0);
+        for _ in 0..1000 {
+            player.charging = Some(CHARGE_POWER_PERCENT);
+            charged += charged_attack(&mut ui, &mut rng, &mut player).damage;
+            twice += charged_attack(&mut ui, &mut rng, &mut player).damage + charged_attack(&mut ui, &mut rng, &mut player).damage;
+        }
+        assert!(charged > twice * 5 / 4);
+    }
+
+    #[test]
+    fn actions_are_picked_by_weight() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let enemy = enemy_with(vec![
+            EnemyAction { action: Action::Attack, weight: 3, hpBelow: None, once: false },
+            EnemyAction { action: Action::Heal(5), weight: 1, hpBelow: None, once: false },
+        ]);
+        let heals = (0..10_000).filter(|_| choose_action(&mut rng, &enemy) == Some(1)).count();
+        assert!((2200..2800).contains(&heals));
+    }
+}
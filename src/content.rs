@@ -0,0 +1,523 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::*;
+use rand::Rng;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::class::{Class, ClassDef};
+use crate::item::{Effect, Item};
+use crate::shop::{Goods, Shop};
+use crate::combat::{Action, Element, Enemy, EnemyAction};
+use crate::player::{Spell, Weapon};
+use crate::rules::{ExpCurve, Rules};
+use crate::skill::{Skill, SkillEffect};
+use crate::status::{StatusApply, StatusKind, Statuses};
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct StatRange {
+    // An inclusive range that a stat is rolled from
+    pub min: i32,
+    pub max: i32,
+}
+
+impl StatRange {
+    pub fn roll(&self, rng: &mut impl Rng) -> i32 {
+        rng.gen_range(self.min..=self.max)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct EnemyTemplate {
+    // What an enemy can look like before it is rolled
+    pub name: String,
+    pub color: String,
+    pub hp: StatRange,
+    pub attack: StatRange,
+    pub agility: StatRange,
+    // Percent of each element's damage shrugged off, negative for a weakness
+    #[serde(default)]
+    pub resistances: BTreeMap<Element, i32>,
+    #[serde(default)]
+    pub immunities: Vec<StatusKind>,
+    // Statuses its attacks can inflict
+    #[serde(default)]
+    pub onHit: Vec<StatusApply>,
+    // What it can do on its turn, a basic attack if empty
+    #[serde(default)]
+    pub actions: Vec<EnemyAction>,
+}
+
+impl EnemyTemplate {
+    pub fn spawn(&self, rng: &mut impl Rng) -> Enemy {
+        let color: Color = self.color.parse().unwrap_or(Color::White);
+        let hp = self.hp.roll(rng);
+        Enemy {
+            name: self.name.color(color).to_string(),
+            hp,
+            maxHp: hp,
+            attack: self.attack.roll(rng),
+            agility: self.agility.roll(rng),
+            expToGive: hp.max(2) / 2,
+            goldToGive: hp.max(2) / 2,
+            resistances: self.resistances.clone(),
+            immunities: self.immunities.clone(),
+            onHit: self.onHit.clone(),
+            statuses: Statuses::default(),
+            actions: self.actions.clone(),
+            usedActions: Vec::new(),
+            windingUp: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Location {
+    // A place to look for a fight and the enemies that live there
+    pub name: String,
+    pub description: String,
+    pub enemies: Vec<Encounter>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Encounter {
+    // One enemy by name, or a group of them fought all at once
+    Single(String),
+    Group(Vec<String>),
+}
+
+impl Encounter {
+    pub fn names(&self) -> &[String] {
+        match self {
+            Encounter::Single(name) => std::slice::from_ref(name),
+            Encounter::Group(names) => names,
+        }
+    }
+}
+
+pub struct Content {
+    // Everything the game reads from the content directory
+    pub enemies: Vec<EnemyTemplate>,
+    pub locations: Vec<Location>,
+    pub weapons: Vec<Weapon>,
+    pub spells: Vec<Spell>,
+    pub skills: Vec<Skill>,
+    pub items: Vec<Item>,
+    pub shops: Vec<Shop>,
+    pub classes: Vec<ClassDef>,
+    pub rules: Rules,
+}
+
+#[derive(Debug)]
+pub struct ContentError {
+    pub file: PathBuf,
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.field.is_empty() {
+            write!(f, "{}: {}", self.file.display(), self.message)
+        } else {
+            write!(f, "{}: {}: {}", self.file.display(), self.field, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+// The content directory as it was at build time, so the game runs from wherever it is launched
+const BUNDLED: &[(&str, &str)] = &[
+    ("enemies.json", include_str!("../content/enemies.json")),
+    ("locations.json", include_str!("../content/locations.json")),
+    ("weapons.json", include_str!("../content/weapons.json")),
+    ("spells.json", include_str!("../content/spells.json")),
+    ("skills.json", include_str!("../content/skills.json")),
+    ("items.json", include_str!("../content/items.json")),
+    ("shops.json", include_str!("../content/shops.json")),
+    ("classes.json", include_str!("../content/classes.json")),
+    ("rules.json", include_str!("../content/rules.json")),
+];
+
+impl Content {
+    pub fn load(dir: &Path) -> Result<Self, ContentError> {
+        Content::from_files(dir, |file| fs::read_to_string(file).map_err(|e| e.to_string()))
+    }
+
+    // The content built into the binary
+    pub fn bundled() -> Result<Self, ContentError> {
+        Content::from_files(Path::new("content"), |file| {
+            BUNDLED.iter()
+                .find(|(name, _)| file.file_name().is_some_and(|f| f == *name))
+                .map(|(_, data)| data.to_string())
+                .ok_or_else(|| "not bundled with the game".to_string())
+        })
+    }
+
+    fn from_files(dir: &Path, read: impl Fn(&Path) -> Result<String, String>) -> Result<Self, ContentError> {
+        let file = |name: &str| {
+            let path = dir.join(name);
+            read(&path).map(|data| (path.clone(), data)).map_err(|e| error(&path, String::new(), e))
+        };
+        let content = Content {
+            enemies: parse_file(file("enemies.json")?)?,
+            locations: parse_file(file("locations.json")?)?,
+            weapons: parse_file(file("weapons.json")?)?,
+            spells: parse_file(file("spells.json")?)?,
+            skills: parse_file(file("skills.json")?)?,
+            items: parse_file(file("items.json")?)?,
+            shops: parse_file(file("shops.json")?)?,
+            classes: parse_file(file("classes.json")?)?,
+            rules: parse_file(file("rules.json")?)?,
+        };
+        content.validate(dir)?;
+        Ok(content)
+    }
+
+    pub fn enemy(&self, name: &str) -> Option<&EnemyTemplate> {
+        self.enemies.iter().find(|e| e.name == name)
+    }
+
+    pub fn weapon(&self, name: &str) -> Option<&Weapon> {
+        self.weapons.iter().find(|w| w.name == name)
+    }
+
+    pub fn spell(&self, name: &str) -> Option<&Spell> {
+        self.spells.iter().find(|s| s.name == name)
+    }
+
+    pub fn class(&self, class: Class) -> &ClassDef {
+        self.classes.iter().find(|c| c.class == class).expect("validation requires every class to be defined")
+    }
+
+    pub fn item(&self, id: &str) -> Option<&Item> {
+        self.items.iter().find(|i| i.id == id)
+    }
+
+    fn validate(&self, dir: &Path) -> Result<(), ContentError> {
+        let file = dir.join("enemies.json");
+        let mut names = HashSet::new();
+        for (i, enemy) in self.enemies.iter().enumerate() {
+            check_name(&file, i, &enemy.name, &mut names)?;
+            check_range(&file, &format!("[{}].hp", i), enemy.hp, 1)?;
+            check_range(&file, &format!("[{}].attack", i), enemy.attack, 0)?;
+            check_range(&file, &format!("[{}].agility", i), enemy.agility, 0)?;
+            if enemy.color.parse::<Color>().is_err() {
+                return Err(error(&file, format!("[{}].color", i), format!("unknown color \"{}\"", enemy.color)));
+            }
+            for (j, apply) in enemy.onHit.iter().enumerate() {
+                check_status(&file, &format!("[{}].onHit[{}]", i, j), apply)?;
+            }
+            for (j, action) in enemy.actions.iter().enumerate() {
+                let field = format!("[{}].actions[{}]", i, j);
+                if action.weight == 0 {
+                    return Err(error(&file, format!("{}.weight", field), "must be at least 1".to_string()));
+                }
+                if action.hpBelow.is_some_and(|percent| !(1..=100).contains(&percent)) {
+                    return Err(error(&file, format!("{}.hpBelow", field), "must be between 1 and 100".to_string()));
+                }
+                match &action.action {
+                    Action::HeavyAttack { powerPercent } if *powerPercent < 100 => {
+                        return Err(error(&file, format!("{}.action.heavyAttack.powerPercent", field), "must be at least 100".to_string()));
+                    }
+                    Action::Heal(amount) if *amount <= 0 => {
+                        return Err(error(&file, format!("{}.action.heal", field), "must restore at least 1".to_string()));
+                    }
+                    Action::CallAlly(name) if self.enemy(name).is_none() => {
+                        return Err(error(&file, format!("{}.action.callAlly", field), format!("unknown enemy \"{}\"", name)));
+                    }
+                    Action::ApplyStatus(apply) => check_status(&file, &format!("{}.action.applyStatus", field), apply)?,
+                    _ => (),
+                }
+            }
+            for (element, resistance) in &enemy.resistances {
+                if !(-100..=100).contains(resistance) {
+                    return Err(error(&file, format!("[{}].resistances.{}", i, element.name()), "must be between -100 and 100".to_string()));
+                }
+            }
+        }
+
+        let file = dir.join("locations.json");
+        let mut names = HashSet::new();
+        for (i, location) in self.locations.iter().enumerate() {
+            check_name(&file, i, &location.name, &mut names)?;
+            if location.enemies.is_empty() {
+                return Err(error(&file, format!("[{}].enemies", i), "a location needs at least one enemy".to_string()));
+            }
+            for (j, encounter) in location.enemies.iter().enumerate() {
+                if encounter.names().is_empty() {
+                    return Err(error(&file, format!("[{}].enemies[{}]", i, j), "a group needs at least one enemy".to_string()));
+                }
+                for enemy in encounter.names() {
+                    if self.enemy(enemy).is_none() {
+                        return Err(error(&file, format!("[{}].enemies[{}]", i, j), format!("unknown enemy \"{}\"", enemy)));
+                    }
+                }
+            }
+        }
+
+        let file = dir.join("weapons.json");
+        let mut names = HashSet::new();
+        for (i, weapon) in self.weapons.iter().enumerate() {
+            check_name(&file, i, &weapon.name, &mut names)?;
+            check_range(&file, &format!("[{}].maxDamage", i), StatRange { min: weapon.minDamage, max: weapon.maxDamage }, 0)?;
+        }
+
+        let file = dir.join("spells.json");
+        let mut names = HashSet::new();
+        for (i, spell) in self.spells.iter().enumerate() {
+            check_name(&file, i, &spell.name, &mut names)?;
+            if spell.mpCost < 0 {
+                return Err(error(&file, format!("[{}].mpCost", i), "must not be negative".to_string()));
+            }
+            if spell.damage < 0 {
+                return Err(error(&file, format!("[{}].damage", i), "must not be negative".to_string()));
+            }
+            for (j, apply) in spell.statuses.iter().enumerate() {
+                check_status(&file, &format!("[{}].statuses[{}]", i, j), apply)?;
+            }
+        }
+
+        let file = dir.join("skills.json");
+        let mut names = HashSet::new();
+        for (i, skill) in self.skills.iter().enumerate() {
+            check_name(&file, i, &skill.name, &mut names)?;
+            if skill.cooldown < 0 {
+                return Err(error(&file, format!("[{}].cooldown", i), "must not be negative".to_string()));
+            }
+            match skill.effect {
+                SkillEffect::Strike { hits, .. } if hits < 1 => {
+                    return Err(error(&file, format!("[{}].effect.strike.hits", i), "must be at least 1".to_string()));
+                }
+                SkillEffect::Strike { powerPercent, .. } if powerPercent < 1 => {
+                    return Err(error(&file, format!("[{}].effect.strike.powerPercent", i), "must be at least 1".to_string()));
+                }
+                SkillEffect::Taunt { turns } if turns < 1 => {
+                    return Err(error(&file, format!("[{}].effect.taunt.turns", i), "must be at least 1".to_string()));
+                }
+                _ => (),
+            }
+            for (j, weapon) in skill.weapons.iter().enumerate() {
+                if self.weapon(weapon).is_none() {
+                    return Err(error(&file, format!("[{}].weapons[{}]", i, j), format!("unknown weapon \"{}\"", weapon)));
+                }
+            }
+        }
+
+        let file = dir.join("items.json");
+        let mut names = HashSet::new();
+        let mut ids = HashSet::new();
+        for (i, item) in self.items.iter().enumerate() {
+            check_name(&file, i, &item.name, &mut names)?;
+            // Ids are what saves store, so they must stay stable once shipped
+            if item.id.is_empty() || !item.id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+                return Err(error(&file, format!("[{}].id", i), "must be lowercase letters, digits and underscores".to_string()));
+            }
+            if !ids.insert(item.id.clone()) {
+                return Err(error(&file, format!("[{}].id", i), format!("duplicate id \"{}\"", item.id)));
+            }
+            if item.maxStack == 0 {
+                return Err(error(&file, format!("[{}].maxStack", i), "must be at least 1".to_string()));
+            }
+            for (j, effect) in item.effects.iter().enumerate() {
+                let field = format!("[{}].effects[{}]", i, j);
+                match effect {
+                    Effect::Heal(amount) | Effect::RestoreMp(amount) if *amount <= 0 => {
+                        return Err(error(&file, field, "must restore at least 1".to_string()));
+                    }
+                    Effect::Buff { turns, .. } if *turns <= 0 => {
+                        return Err(error(&file, format!("{}.turns", field), "must be at least 1".to_string()));
+                    }
+                    Effect::ApplyStatus(apply) => check_status(&file, &format!("{}.applyStatus", field), apply)?,
+                    _ => (),
+                }
+            }
+        }
+
+        let file = dir.join("shops.json");
+        let mut names = HashSet::new();
+        for (i, shop) in self.shops.iter().enumerate() {
+            check_name(&file, i, &shop.name, &mut names)?;
+            let mut goods = HashSet::new();
+            for (j, entry) in shop.stock.iter().enumerate() {
+                let field = format!("[{}].stock[{}]", i, j);
+                let known = match &entry.goods {
+                    Goods::Item(name) => self.item(name).is_some(),
+                    Goods::Weapon(name) => self.weapon(name).is_some(),
+                    Goods::Spell(name) => self.spell(name).is_some(),
+                };
+                if !known {
+                    return Err(error(&file, field, format!("unknown goods \"{}\"", entry.key())));
+                }
+                if !goods.insert(entry.key().to_lowercase()) {
+                    return Err(error(&file, field, format!("\"{}\" is stocked twice", entry.key())));
+                }
+                check_price(&file, &format!("{}.price", field), entry.price)?;
+                if let Some(sellPrice) = entry.sellPrice {
+                    check_price(&file, &format!("{}.sellPrice", field), sellPrice)?;
+                }
+                if entry.limit == Some(0) {
+                    return Err(error(&file, format!("{}.limit", field), "must be at least 1".to_string()));
+                }
+                if entry.restock == Some(0) {
+                    return Err(error(&file, format!("{}.restock", field), "must be at least 1".to_string()));
+                }
+                if entry.restock.is_some() && entry.limit.is_none() {
+                    return Err(error(&file, format!("{}.restock", field), "only limited stock can restock".to_string()));
+                }
+            }
+        }
+
+        let file = dir.join("classes.json");
+        for class in Class::ALL {
+            match self.classes.iter().filter(|c| c.class == class).count() {
+                1 => (),
+                0 => return Err(error(&file, String::new(), format!("{} is not defined", class.name()))),
+                _ => return Err(error(&file, String::new(), format!("{} is defined twice", class.name()))),
+            }
+        }
+        for (i, def) in self.classes.iter().enumerate() {
+            let start = &def.start;
+            if start.hp < 1 {
+                return Err(error(&file, format!("[{}].start.hp", i), "must be at least 1".to_string()));
+            }
+            for (field, value) in [("mp", start.mp), ("strength", start.strength), ("defense", start.defense), ("agility", start.agility), ("intelligence", start.intelligence), ("gold", start.gold)] {
+                if value < 0 {
+                    return Err(error(&file, format!("[{}].start.{}", i, field), "must not be negative".to_string()));
+                }
+            }
+            if let Some(weapon) = &start.weapon
+                && self.weapon(weapon).is_none()
+            {
+                return Err(error(&file, format!("[{}].start.weapon", i), format!("unknown weapon \"{}\"", weapon)));
+            }
+            for (j, spell) in start.spells.iter().enumerate() {
+                if self.spell(spell).is_none() {
+                    return Err(error(&file, format!("[{}].start.spells[{}]", i, j), format!("unknown spell \"{}\"", spell)));
+                }
+            }
+            for id in start.items.keys() {
+                if self.item(id).is_none() {
+                    return Err(error(&file, format!("[{}].start.items.{}", i, id), format!("unknown item \"{}\"", id)));
+                }
+            }
+            let growth = def.growth;
+            for (field, value) in [("strength", growth.strength), ("defense", growth.defense), ("maxHp", growth.maxHp), ("maxMp", growth.maxMp), ("agility", growth.agility), ("intelligence", growth.intelligence)] {
+                if value < 0 {
+                    return Err(error(&file, format!("[{}].growth.{}", i, field), "must not be negative".to_string()));
+                }
+            }
+        }
+
+        let file = dir.join("rules.json");
+        check_percent(&file, "defeat.goldLossPercent", self.rules.defeat.goldLossPercent)?;
+        check_percent(&file, "defeat.respawnHpPercent", self.rules.defeat.respawnHpPercent)?;
+        check_price(&file, "inn.price", self.rules.inn.price)?;
+        let points = &self.rules.statPoints;
+        for (field, value) in [("perLevel", points.perLevel), ("hpPerPoint", points.hpPerPoint), ("mpPerPoint", points.mpPerPoint)] {
+            if value < 1 {
+                return Err(error(&file, format!("statPoints.{}", field), "must be at least 1".to_string()));
+            }
+        }
+        check_price(&file, "statPoints.respecPrice", points.respecPrice)?;
+        let leveling = &self.rules.leveling;
+        if leveling.levelCap < 1 {
+            return Err(error(&file, "leveling.levelCap".to_string(), "must be at least 1".to_string()));
+        }
+        match &leveling.expCurve {
+            ExpCurve::Table(table) => {
+                if table.len() < leveling.levelCap as usize {
+                    return Err(error(&file, "leveling.expCurve".to_string(), format!("needs an entry for each of the {} levels below the cap", leveling.levelCap)));
+                }
+                if let Some(i) = table.iter().position(|exp| *exp < 1) {
+                    return Err(error(&file, format!("leveling.expCurve[{}]", i), "must be at least 1".to_string()));
+                }
+            }
+            ExpCurve::Linear { base, perLevel } => {
+                if *base < 1 {
+                    return Err(error(&file, "leveling.expCurve.base".to_string(), "must be at least 1".to_string()));
+                }
+                if *perLevel < 1 {
+                    return Err(error(&file, "leveling.expCurve.perLevel".to_string(), "must be at least 1".to_string()));
+                }
+                // exp_to_level works in i32, so the cost at the cap has to fit in one
+                if perLevel.checked_mul(leveling.levelCap).and_then(|exp| exp.checked_add(*base)).is_none() {
+                    return Err(error(&file, "leveling.expCurve".to_string(), format!("base + perLevel * levelCap must be at most {}", i32::MAX)));
+                }
+            }
+        }
+        if self.rules.regen.hpPerFight < 0 {
+            return Err(error(&file, "regen.hpPerFight".to_string(), "must not be negative".to_string()));
+        }
+        if self.rules.regen.mpPerFight < 0 {
+            return Err(error(&file, "regen.mpPerFight".to_string(), "must not be negative".to_string()));
+        }
+        Ok(())
+    }
+}
+
+fn parse_file<T: DeserializeOwned>((file, data): (PathBuf, String)) -> Result<T, ContentError> {
+    let file = file.as_path();
+    let deserializer = &mut serde_json::Deserializer::from_str(&data);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let field = e.path().to_string();
+        error(file, if field == "." { String::new() } else { field }, e.into_inner().to_string())
+    })
+}
+
+fn error(file: &Path, field: String, message: String) -> ContentError {
+    ContentError { file: file.to_path_buf(), field, message }
+}
+
+fn check_name(file: &Path, index: usize, name: &str, seen: &mut HashSet<String>) -> Result<(), ContentError> {
+    if name.trim().is_empty() {
+        return Err(error(file, format!("[{}].name", index), "must not be empty".to_string()));
+    }
+    // Names are typed by the player, so they have to be unique regardless of case
+    if !seen.insert(name.to_lowercase()) {
+        return Err(error(file, format!("[{}].name", index), format!("duplicate name \"{}\"", name)));
+    }
+    Ok(())
+}
+
+fn check_range(file: &Path, field: &str, range: StatRange, lowest: i32) -> Result<(), ContentError> {
+    if range.min < lowest {
+        return Err(error(file, field.to_string(), format!("min must be at least {}", lowest)));
+    }
+    if range.min > range.max {
+        return Err(error(file, field.to_string(), format!("min ({}) is greater than max ({})", range.min, range.max)));
+    }
+    Ok(())
+}
+
+fn check_status(file: &Path, field: &str, apply: &StatusApply) -> Result<(), ContentError> {
+    if apply.turns < 1 {
+        return Err(error(file, format!("{}.turns", field), "must be at least 1".to_string()));
+    }
+    if apply.potency < 0 {
+        return Err(error(file, format!("{}.potency", field), "must not be negative".to_string()));
+    }
+    if !(1..=100).contains(&apply.chance) {
+        return Err(error(file, format!("{}.chance", field), "must be between 1 and 100".to_string()));
+    }
+    Ok(())
+}
+
+fn check_percent(file: &Path, field: &str, percent: i32) -> Result<(), ContentError> {
+    if !(0..=100).contains(&percent) {
+        return Err(error(file, field.to_string(), "must be between 0 and 100".to_string()));
+    }
+    Ok(())
+}
+
+fn check_price(file: &Path, field: &str, price: i32) -> Result<(), ContentError> {
+    if price < 0 {
+        return Err(error(file, field.to_string(), "must not be negative".to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+use std::io;
+use std::process::Command;
+
+pub trait Frontend {
+    // Asks the player for a line of input, shown after the question if it isn't empty
+    fn prompt(&mut self, question: &str) -> String;
+    fn show(&mut self, message: &str);
+    fn clear(&mut self);
+
+    // Shows a framed list of options and reads the player's choice
+    fn menu(&mut self, title: &str, options: &[String]) -> String {
+        self.show("-------------------------");
+        self.show(title);
+        for option in options {
+            self.show(option);
+        }
+        self.show("-------------------------");
+        self.prompt("")
+    }
+}
+
+pub struct TerminalFrontend;
+
+impl Frontend for TerminalFrontend {
+    fn prompt(&mut self, question: &str) -> String {
+        if !question.is_empty() {
+            println!("{}", question);
+        }
+        let mut input = String::new();
+        let read = io::stdin().read_line(&mut input).expect("Failed to read line");
+        // Treat a closed stdin like the player typing quit, so every menu can be left
+        if read == 0 {
+            return "quit".to_string();
+        }
+        input.trim().to_string()
+    }
+
+    fn show(&mut self, message: &str) {
+        println!("{}", message);
+    }
+
+    fn clear(&mut self) {
+        if cfg!(target_os = "windows") {
+            Command::new("cmd")
+                .args(["/c", "cls"])
+                .status()
+                .expect("Failed to clear screen!");
+        } else {
+            Command::new("clear")
+                .status()
+                .expect("Failed to clear screen!");
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ScriptedFrontend {
+    // Plays back a fixed list of inputs and records everything shown, for tests and tools
+    inputs: VecDeque<String>,
+    pub output: Vec<String>,
+}
+
+impl ScriptedFrontend {
+    pub fn new(inputs: &[&str]) -> Self {
+        ScriptedFrontend {
+            inputs: inputs.iter().map(|i| i.to_string()).collect(),
+            output: Vec::new(),
+        }
+    }
+
+    pub fn transcript(&self) -> String {
+        self.output.join("\n")
+    }
+}
+
+impl Frontend for ScriptedFrontend {
+    fn prompt(&mut self, question: &str) -> String {
+        if !question.is_empty() {
+            self.output.push(question.to_string());
+        }
+        // Running out of script quits, so a session can never hang waiting for input
+        self.inputs.pop_front().unwrap_or_else(|| "quit".to_string())
+    }
+
+    fn show(&mut self, message: &str) {
+        self.output.push(message.to_string());
+    }
+
+    fn clear(&mut self) {}
+}
@@ -0,0 +1,431 @@
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use colored::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::class::Class;
+use crate::combat::{look_For_Fight2, FightOutcome};
+use crate::content::Content;
+use crate::frontend::Frontend;
+use crate::inn::inn;
+use crate::player::Player;
+use crate::save;
+use crate::shop::Shop;
+use crate::shrine::{auto_level, shrine};
+
+pub struct Game {
+    // State that lives for a whole session; every random roll goes through rng
+    pub seed: u64,
+    pub rng: StdRng,
+    pub saveDir: PathBuf,
+    // Where older versions of the game saved, next to wherever it was run from
+    pub oldSaveDir: PathBuf,
+}
+
+impl Game {
+    pub fn new(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(rand::random);
+        Game {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            saveDir: save::default_save_dir(),
+            oldSaveDir: PathBuf::from("."),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    // What can be set from the command line
+    pub seed: Option<u64>,
+    pub saveDir: Option<PathBuf>,
+}
+
+// Reads `--seed N` and `--save-dir PATH` from the command line, either also as `--flag=value`.
+// args starts with the program name, anything else that isn't one of the flags is an error.
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        match flag {
+            "--seed" => {
+                let value = inline.or_else(|| args.next().cloned()).ok_or("--seed needs a number")?;
+                options.seed = Some(value.parse().map_err(|_| format!("--seed needs a number, got \"{}\"", value))?);
+            }
+            "--save-dir" => {
+                let value = inline.or_else(|| args.next().cloned()).ok_or("--save-dir needs a path")?;
+                options.saveDir = Some(PathBuf::from(value));
+            }
+            _ => return Err(format!("unknown argument \"{}\", expected --seed or --save-dir", arg)),
+        }
+    }
+    Ok(options)
+}
+
+// Asks for a new character's name until it is one that can be saved, None if the player backs out
+fn ask_name(ui: &mut dyn Frontend, question: &str) -> Option<String> {
+    loop {
+        let input = ui.prompt(question);
+        if matches!(input.as_str(), "quit" | "Quit" | "back" | "Back") {
+            return None;
+        }
+        match save::sanitize_name(&input) {
+            Ok(name) => return Some(name),
+            Err(e) => ui.show(&e),
+        }
+    }
+}
+
+fn pick_save(ui: &mut dyn Frontend, saves: &[save::SaveSummary], question: &str) -> Option<String> {
+    let input = ui.prompt(question);
+    let found = saves.iter().find(|s| s.name.eq_ignore_ascii_case(input.trim())).map(|s| s.name.clone());
+    if found.is_none() && !matches!(input.as_str(), "quit" | "Quit" | "back" | "Back") {
+        ui.show(&format!("There is no character called {}!", input));
+    }
+    found
+}
+
+// Saves used to live in the working directory, so characters left there are offered a move
+// to the save directory instead of silently disappearing from the character list
+fn offer_import(ui: &mut dyn Frontend, game: &Game) {
+    let (from, to) = (&game.oldSaveDir, &game.saveDir);
+    if fs::canonicalize(from).ok() == fs::canonicalize(to).ok() {
+        return;
+    }
+    let declined = save::declined_imports(to);
+    let saves: Vec<save::SaveSummary> = save::list_saves(from).into_iter().filter(|s| !declined.contains(&s.name)).collect();
+    if saves.is_empty() {
+        return;
+    }
+    let names: Vec<&str> = saves.iter().map(|s| s.name.as_str()).collect();
+    ui.show(&format!("Found characters saved by an older version in {}: {}", from.display(), names.join(", ")));
+    let answer = ui.prompt(&format!("Move them to {}? (yes/no)", to.display()));
+    if !matches!(answer.as_str(), "yes" | "Yes" | "y" | "Y") {
+        match save::decline_imports(to, &names) {
+            Ok(()) => ui.show("They have been left where they are and won't be offered again."),
+            Err(e) => ui.show(&format!("They have been left where they are. Could not remember that: {}", e)),
+        }
+        return;
+    }
+    for name in names {
+        match save::import_save(from, to, name) {
+            Ok(()) => ui.show(&format!("{} has been imported.", name)),
+            Err(e) => ui.show(&format!("Could not import {}: {}", name, e)),
+        }
+    }
+}
+
+// The startup menu, returns the name to play as or None to quit
+fn choose_character(ui: &mut dyn Frontend, dir: &Path) -> Option<String> {
+    loop {
+        let saves = save::list_saves(dir);
+        if saves.is_empty() {
+            return ask_name(ui, "Enter your name:");
+        }
+        let mut options: Vec<String> = saves.iter()
+            .map(|s| {
+                let lastPlayed = s.savedAt.map_or("unknown".to_string(), |t| format!("{} UTC", save::format_timestamp(t)));
+                format!("> {} - Level {}, {} gold, last played {}", s.name, s.level, s.gold, lastPlayed)
+            })
+            .collect();
+        options.push("> New character".to_string());
+        options.push("> Delete".to_string());
+        options.push("> Duplicate".to_string());
+        options.push("> Rename".to_string());
+        options.push("< Quit game".to_string());
+        let input = ui.menu("Choose a character:", &options);
+        // Saved names win over commands, so a character from before names were checked still loads
+        if let Some(save) = saves.iter().find(|s| s.name.eq_ignore_ascii_case(&input)) {
+            return Some(save.name.clone());
+        }
+        match input.as_str() {
+            "new" | "New" | "new character" | "New character" => {
+                if let Some(name) = ask_name(ui, "Enter your name:") {
+                    return Some(name);
+                }
+            }
+            "delete" | "Delete" => {
+                let Some(name) = pick_save(ui, &saves, "Which character would you like to delete?") else { continue };
+                let answer = ui.prompt(&format!("Delete {} for good? (yes/no)", name));
+                if !matches!(answer.as_str(), "yes" | "Yes" | "y" | "Y") {
+                    continue;
+                }
+                match save::delete_save(dir, &name) {
+                    Ok(()) => ui.show(&format!("{} has been deleted.", name)),
+                    Err(e) => ui.show(&format!("Could not delete {}: {}", name, e)),
+                }
+            }
+            "duplicate" | "Duplicate" | "rename" | "Rename" => {
+                let renaming = input.eq_ignore_ascii_case("rename");
+                let verb = if renaming { "rename" } else { "duplicate" };
+                let Some(from) = pick_save(ui, &saves, &format!("Which character would you like to {}?", verb)) else { continue };
+                let Some(to) = ask_name(ui, "Enter the new name:") else { continue };
+                if save::save_path(dir, &to).exists() {
+                    ui.show(&format!("There is already a character called {}!", to));
+                    continue;
+                }
+                let result = if renaming { save::rename_save(dir, &from, &to) } else { save::copy_save(dir, &from, &to) };
+                match result {
+                    Ok(()) if renaming => ui.show(&format!("{} is now called {}.", from, to)),
+                    Ok(()) => ui.show(&format!("{} has been copied to {}.", from, to)),
+                    Err(e) => ui.show(&format!("Could not {} {}: {}", verb, from, e)),
+                }
+            }
+            "quit" | "Quit" => return None,
+            _ => ui.show("Invalid command!"),
+        }
+    }
+}
+
+fn user_Input(ui: &mut dyn Frontend, shops: &[Shop]) -> String { // This is the users input for interacting with the out of combat menu
+    ui.show(&"What would you like to do?".blue());
+    ui.show("> Status");
+    ui.show("> Inventory");
+    ui.show("> Spell List");
+    ui.show("> Use item");
+    for shop in shops {
+        ui.show(&format!("> {}", shop.name));
+    }
+    ui.show("> Shrine");
+    ui.show("> Inn");
+    ui.show("> Look for a fight");
+    ui.show("> Save game");
+    ui.show("< Quit game");
+    ui.prompt("")
+}
+
+// Character creation, returns None if the player quits instead of picking a class
+fn new_character(ui: &mut dyn Frontend, content: &Content, name: String) -> Option<Player> {
+    ui.show(&format!("Creating a new profile for {}.", name));
+    let options: Vec<String> = Class::ALL.iter()
+        .map(|c| format!("> {} - {}", c.name(), content.class(*c).description))
+        .collect();
+    loop {
+        let input = ui.menu("Choose your class:", &options);
+        if matches!(input.as_str(), "quit" | "Quit") {
+            return None;
+        }
+        match Class::ALL.into_iter().find(|c| c.name().eq_ignore_ascii_case(&input)) {
+            Some(class) => {
+                let player = Player::create(name, content.class(class), content);
+                ui.show(&format!("{} sets out as a {}!", player.name, class.name()));
+                return Some(player);
+            }
+            None => ui.show("Invalid class!"),
+        }
+    }
+}
+
+// An unreadable save: fall back on its backup if that still loads, otherwise start over
+// with both files kept aside. Returns None if the player would rather do neither.
+fn recover(ui: &mut dyn Frontend, content: &Content, dir: &Path, name: String) -> Option<Player> {
+    if let Ok(Some(backup)) = save::load_backup(dir, &name) {
+        let answer = ui.prompt(&format!("The backup from {}'s previous save can still be read. Restore it? (yes/no)", name));
+        if matches!(answer.as_str(), "yes" | "Yes" | "y" | "Y") {
+            match save::restore_backup(dir, &name) {
+                Ok(path) => {
+                    ui.show(&format!("The backup has been restored. The unreadable save has been moved to {}.", path.display()));
+                    return Some(backup);
+                }
+                Err(e) => {
+                    ui.show(&format!("Could not restore the backup: {}", e));
+                    return None;
+                }
+            }
+        }
+    }
+    let answer = ui.prompt("Start a new character with this name? The old save will be kept aside. (yes/no)");
+    if !matches!(answer.as_str(), "yes" | "Yes" | "y" | "Y") {
+        return None;
+    }
+    match save::quarantine(dir, &name) {
+        Ok(path) => ui.show(&format!("The old save has been moved to {}.", path.display())),
+        Err(e) => {
+            ui.show(&format!("Could not move the old save out of the way: {}", e));
+            return None;
+        }
+    }
+    match save::quarantine_backup(dir, &name) {
+        Ok(Some(path)) => ui.show(&format!("Its backup has been moved to {}.", path.display())),
+        Ok(None) => (),
+        Err(e) => {
+            ui.show(&format!("Could not move the old backup out of the way: {}", e));
+            return None;
+        }
+    }
+    new_character(ui, content, name)
+}
+
+// Saves without being asked, only a failure is worth interrupting the player for
+fn autosave(ui: &mut dyn Frontend, player: &Player, game: &Game, enabled: bool) {
+    if !enabled {
+        return;
+    }
+    if let Err(e) = player.save(&game.saveDir) {
+        ui.show(&format!("Autosave failed: {}", e));
+    }
+}
+
+fn record_stock(player: &mut Player, shops: &[Shop]) {
+    player.shopStock = shops.iter()
+        .map(|s| (s.name.clone(), s.stock_state()))
+        .filter(|(_, stock)| !stock.is_empty())
+        .collect();
+}
+
+// Permadeath: the character is gone for good, so is their save
+fn game_over(ui: &mut dyn Frontend, player: &Player, game: &Game) {
+    ui.show("-------------------------");
+    ui.show(&format!("{} has fallen. GAME OVER", player.name).red());
+    ui.show("-------------------------");
+    if save::save_path(&game.saveDir, &player.name).exists()
+        && let Err(e) = save::delete_save(&game.saveDir, &player.name)
+    {
+        ui.show(&format!("Could not delete the save: {}", e));
+    }
+}
+
+pub fn play(ui: &mut dyn Frontend, content: &Content, game: &mut Game) { // A whole session, from entering a name to quitting
+    ui.clear();
+    offer_import(ui, game);
+    let Some(name) = choose_character(ui, &game.saveDir) else {
+        return;
+    };
+
+    let mut player = match Player::load(&game.saveDir, &name) {
+        Ok(Some(loaded_player)) => {
+            ui.show("-------------------------");
+            ui.show("Game loaded successfully!");
+            ui.show("Welcome back to Zyveria!");
+            ui.show("-------------------------");
+            loaded_player
+        }
+        Ok(None) => match new_character(ui, content, name) {
+            Some(player) => player,
+            None => return,
+        },
+        Err(e) => {
+            ui.show(&format!("Your save could not be loaded: {}", e));
+            match recover(ui, content, &game.saveDir, name) {
+                Some(player) => player,
+                None => return,
+            }
+        }
+    };
+    player.seed = Some(game.seed);
+
+    // Shops are as this character left them, so stock limits and restock timers carry across sessions
+    let mut shops = content.shops.clone();
+    for shop in &mut shops {
+        if let Some(saved) = player.shopStock.get(&shop.name) {
+            shop.restore(saved);
+        }
+    }
+    let autosaves = content.rules.autosave;
+
+    loop {
+        let choice = user_Input(ui, &shops);
+        match choice.as_str() {
+            "status" | "Status" => player.show_Status(ui, content),
+            "inventory" | "Inventory" => player.show_inventory(ui, content),
+            "spell list" | "Spell List" | "Spells" | "spells" => player.show_spellList(ui, content),
+            "use item" | "Use item" | "use" | "Use" => {
+                let item = ui.prompt("Enter the name of the item you would like to use:");
+                player.use_item(ui, &item, content, false);
+            }
+            "shrine" | "Shrine" => {
+                if shrine(ui, &mut player, content) {
+                    autosave(ui, &player, game, autosaves.afterLevelUp);
+                }
+            }
+            "inn" | "Inn" => {
+                if inn(ui, &mut player, content.rules.inn.price) {
+                    autosave(ui, &player, game, autosaves.afterPurchase);
+                }
+            }
+            "look for a fight" | "Look for a fight" | "look" | "Look" | "fight" | "Fight" => {
+                // A bug mid-fight puts the player back as they were before it instead of losing the session
+                let checkpoint = player.clone();
+                match panic::catch_unwind(AssertUnwindSafe(|| look_For_Fight2(ui, &mut player, content, game))) {
+                    Ok(FightOutcome::NoFight) => (),
+                    Ok(FightOutcome::Defeated) if content.rules.defeat.permadeath => {
+                        game_over(ui, &player, game);
+                        return;
+                    }
+                    Ok(outcome) => {
+                        if outcome == FightOutcome::Defeated {
+                            player.respawn(ui, &content.rules.defeat);
+                        } else {
+                            player.regenerate(ui, &content.rules.regen);
+                        }
+                        shops.iter_mut().for_each(Shop::tick);
+                        record_stock(&mut player, &shops);
+                        let leveled = content.rules.leveling.autoLevel && auto_level(ui, &mut player, content);
+                        autosave(ui, &player, game, autosaves.afterFight || (leveled && autosaves.afterLevelUp));
+                    }
+                    Err(_) => {
+                        player = checkpoint;
+                        ui.show("Something went wrong during the fight! You are back where you were before it.");
+                        autosave(ui, &player, game, true);
+                    }
+                }
+            }
+            "save game" | "Save game" | "save" | "Save" => match player.save(&game.saveDir) {
+                Ok(_) => ui.show("Game saved successfully!"),
+                Err(e) => ui.show(&format!("Failed to save the game: {}", e)),
+            },
+            "quit" | "Quit" => {
+                autosave(ui, &player, game, autosaves.onQuit);
+                break;
+            }
+            _ => match shops.iter_mut().find(|s| s.name.eq_ignore_ascii_case(&choice)) {
+                Some(shop) => {
+                    if shop.visit(ui, &mut player, content) {
+                        record_stock(&mut player, &shops);
+                        autosave(ui, &player, game, autosaves.afterPurchase);
+                    }
+                }
+                None => ui.show("Invalid command!"),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn same_seed_rolls_the_same() {
+        let mut a = Game::new(Some(42));
+        let mut b = Game::new(Some(42));
+        let rollsA: Vec<i32> = (0..20).map(|_| a.rng.gen_range(0..1000)).collect();
+        let rollsB: Vec<i32> = (0..20).map(|_| b.rng.gen_range(0..1000)).collect();
+        assert_eq!(rollsA, rollsB);
+    }
+
+    #[test]
+    fn parses_command_line_arguments() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        let seed = |list: &[&str]| parse_args(&args(list)).map(|o| o.seed);
+        assert_eq!(seed(&["zyveria"]), Ok(None));
+        assert_eq!(seed(&["zyveria", "--seed", "7"]), Ok(Some(7)));
+        assert_eq!(seed(&["zyveria", "--seed=12"]), Ok(Some(12)));
+        assert!(seed(&["zyveria", "--seed"]).is_err());
+        assert!(seed(&["zyveria", "--seed", "abc"]).is_err());
+        let options = parse_args(&args(&["zyveria", "--save-dir", "my saves", "--seed=3"])).unwrap();
+        assert_eq!(options, Options { seed: Some(3), saveDir: Some(PathBuf::from("my saves")) });
+        assert_eq!(parse_args(&args(&["zyveria", "--save-dir=/tmp/z"])).unwrap().saveDir, Some(PathBuf::from("/tmp/z")));
+        assert!(parse_args(&args(&["zyveria", "--save-dir"])).is_err());
+        assert!(parse_args(&args(&["zyveria", "--save-dri", "saves"])).is_err());
+        assert!(parse_args(&args(&["zyveria", "saves"])).is_err());
+    }
+}
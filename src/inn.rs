@@ -0,0 +1,31 @@
+use crate::frontend::Frontend;
+use crate::player::Player;
+
+pub fn inn(ui: &mut dyn Frontend, player: &mut Player, price: i32) -> bool { // Returns whether the player paid for a room
+    ui.clear();
+    let options = vec![
+        format!("> Rest - {} gold, restores all hp and mp.", price),
+        "< Back - Return to the main menu.".to_string(),
+    ];
+    let title = format!("Welcome to the inn! (Hp {}/{}, Mp {}/{}, {} gold)", player.hp, player.maxHp, player.mp, player.maxMp, player.gold);
+    match ui.menu(&title, &options).as_str() {
+        "rest" | "Rest" => {
+            if player.hp >= player.maxHp && player.mp >= player.maxMp {
+                ui.show("You are already fully rested!");
+            }
+            else if player.gold < price {
+                ui.show("You do not have enough gold to rest!");
+            }
+            else {
+                player.gold -= price;
+                player.hp = player.maxHp;
+                player.mp = player.maxMp;
+                ui.show(&format!("{} has rested at the inn for {} gold! Hp and mp are fully restored!", player.name, price));
+                return true;
+            }
+        }
+        "quit" | "Quit" | "back" | "Back" => (),
+        _ => ui.show("Invalid service!"),
+    }
+    false
+}
@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::item::Item;
+
+// How many items the player can carry in total, across every stack
+pub const CARRY_LIMIT: u32 = 40;
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(transparent)]
+pub struct Inventory {
+    // How many of each item id the player is carrying
+    stacks: BTreeMap<String, u32>,
+}
+
+impl Inventory {
+    pub fn count(&self, id: &str) -> u32 {
+        self.stacks.get(id).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.stacks.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.stacks.iter().map(|(id, count)| (id.as_str(), *count))
+    }
+
+    // Why the item can't be added, if it can't
+    pub fn check_add(&self, item: &Item) -> Result<(), String> {
+        if self.count(&item.id) >= item.maxStack {
+            return Err(format!("You can't carry more than {} {}!", item.maxStack, item.name));
+        }
+        if self.total() >= CARRY_LIMIT {
+            return Err(format!("You can't carry more than {} items!", CARRY_LIMIT));
+        }
+        Ok(())
+    }
+
+    pub fn add(&mut self, item: &Item) -> Result<(), String> {
+        self.check_add(item)?;
+        *self.stacks.entry(item.id.clone()).or_insert(0) += 1;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        match self.stacks.get_mut(id) {
+            Some(count) => {
+                *count -= 1;
+                if *count == 0 {
+                    self.stacks.remove(id);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use crate::status::{StatusApply, StatusKind};
+
+#[derive(Deserialize, Clone)]
+pub struct Item {
+    // Our item details
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub effects: Vec<Effect>,
+    // How many of this item fit in a single inventory stack
+    #[serde(default = "default_max_stack")]
+    pub maxStack: u32,
+}
+
+fn default_max_stack() -> u32 {
+    10
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum Effect {
+    // Everything an item can do when it is used, applied in order
+    Heal(i32),
+    RestoreMp(i32),
+    Buff { stat: Stat, amount: i32, turns: i32 },
+    // Removes every active buff with a negative amount and every harmful status
+    Cure,
+    ApplyStatus(StatusApply),
+    CureStatus(Vec<StatusKind>),
+    // Ends the current fight, can't be used outside of one
+    Escape,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Stat {
+    Strength,
+    Defense,
+    Agility,
+    Intelligence,
+}
+
+impl Stat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::Strength => "strength",
+            Stat::Defense => "defense",
+            Stat::Agility => "agility",
+            Stat::Intelligence => "intelligence",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Buff {
+    // A temporary change to a stat that counts down each combat turn
+    pub stat: Stat,
+    pub amount: i32,
+    pub turnsLeft: i32,
+}
+
+#[derive(PartialEq)]
+pub enum ItemUse {
+    // What happened when the player tried to use an item
+    NotUsed,
+    Used,
+    Escaped,
+}
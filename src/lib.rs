@@ -0,0 +1,18 @@
+#![allow(non_snake_case)]
+
+pub mod class;
+pub mod combat;
+pub mod content;
+pub mod frontend;
+pub mod game;
+pub mod inn;
+pub mod inventory;
+pub mod item;
+pub mod player;
+pub mod points;
+pub mod rules;
+pub mod save;
+pub mod shop;
+pub mod shrine;
+pub mod skill;
+pub mod status;
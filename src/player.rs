@@ -0,0 +1,386 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::class::{Class, ClassDef, Growth};
+use crate::combat::{spell_cost, Element};
+use crate::content::Content;
+use crate::frontend::Frontend;
+use crate::inventory::{Inventory, CARRY_LIMIT};
+use crate::item::{Buff, Effect, Item, ItemUse, Stat};
+use crate::points::Allocation;
+use crate::rules::{Defeat, Regen};
+use crate::shop::StockState;
+use crate::status::{StatusApply, Statuses};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Player {
+    // Our player details
+    pub name: String,
+    pub class: Class,
+    // Classes picked up at the shrine when multiclassing is allowed
+    #[serde(default)]
+    pub extraClasses: Vec<Class>,
+    pub hp: i32,
+    pub maxHp: i32,
+    pub mp: i32,
+    pub maxMp: i32,
+    pub equippedWeapon: Weapon,
+    pub level: i32,
+    pub strength: i32,
+    pub defense: i32,
+    pub agility: i32,
+    pub intelligence: i32,
+    // Unspent points from level ups taken at the shrine as stat points
+    pub statPoints: i32,
+    pub allocated: Allocation,
+    pub exp: i32,
+    pub gold: i32,
+    pub inventory: Inventory,
+    pub spellInventory: Vec<Spell>,
+    // How far each shop's stock has been bought down, by shop name then stock entry
+    pub shopStock: BTreeMap<String, BTreeMap<String, StockState>>,
+    // The seed of the session that last saved, so a reported save can be replayed
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(skip)]
+    pub buffs: Vec<Buff>,
+    #[serde(skip)]
+    pub statuses: Statuses,
+    // Extra defense from defending, used up by the next hit taken
+    #[serde(skip)]
+    pub guard: i32,
+    // The power of the next attack after charging, like an enemy's heavy attack wind-up
+    #[serde(skip)]
+    pub charging: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Weapon {
+    // Our weapon details
+    pub name: String,
+    pub description: String,
+    pub minDamage: i32,
+    pub maxDamage: i32,
+    // Who can wield it, anyone if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<Class>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Spell {
+    // Our spell details
+    pub name: String,
+    pub description: String,
+    pub mpCost: i32,
+    pub damage: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<Element>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statuses: Vec<StatusApply>,
+    // Hits every enemy in the fight instead of one
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub area: bool,
+    // Who can learn it, anyone if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<Class>,
+}
+
+impl Weapon {
+    pub fn fist() -> Self { // What the player fights with when they have no weapon
+        Weapon {
+            name: "Fist".to_string(),
+            description: "A rusty fist.".to_string(),
+            minDamage: 1,
+            maxDamage: 5,
+            classes: Vec::new(),
+        }
+    }
+}
+
+impl Player {
+    pub fn new(name: String) -> Self { // A bare level 0 warrior with nothing, before any class kit is handed out
+        Player {
+            name,
+            class: Class::Warrior,
+            extraClasses: Vec::new(),
+            hp: 100,
+            maxHp: 100,
+            mp: 5,
+            maxMp: 5,
+            equippedWeapon: Weapon::fist(),
+            exp: 0,
+            gold: 0,
+            inventory: Inventory::default(),
+            spellInventory: Vec::new(),
+            shopStock: BTreeMap::new(),
+            seed: None,
+            buffs: Vec::new(),
+            statuses: Statuses::default(),
+            guard: 0,
+            charging: None,
+            level: 0,
+            strength: 0,
+            defense: 0,
+            agility: 0,
+            intelligence: 0,
+            statPoints: 0,
+            allocated: Allocation::default(),
+        }
+    }
+
+    pub fn create(name: String, def: &ClassDef, content: &Content) -> Self { // A new character with their class's starting kit
+        let start = &def.start;
+        let mut player = Player::new(name);
+        player.class = def.class;
+        player.hp = start.hp;
+        player.maxHp = start.hp;
+        player.mp = start.mp;
+        player.maxMp = start.mp;
+        player.strength = start.strength;
+        player.defense = start.defense;
+        player.agility = start.agility;
+        player.intelligence = start.intelligence;
+        player.gold = start.gold;
+        // Validation guarantees the kit only names known weapons, spells and items
+        if let Some(weapon) = start.weapon.as_deref().and_then(|w| content.weapon(w)) {
+            player.equippedWeapon = weapon.clone();
+        }
+        player.spellInventory = start.spells.iter().filter_map(|s| content.spell(s)).cloned().collect();
+        for (id, count) in &start.items {
+            if let Some(item) = content.item(id) {
+                for _ in 0..*count {
+                    if player.inventory.add(item).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+        player
+    }
+
+    pub fn show_Status(&self, ui: &mut dyn Frontend, content: &Content) { // Can be called at any time to show the player's status
+        ui.clear();
+        ui.show("-------------------------");
+        ui.show(&format!("{}, you are a Level {} {}", self.name, self.level, self.class_name()));
+        ui.show(&format!("HP: {}/{}", self.hp, self.maxHp));
+        ui.show(&format!("MP: {}/{}", self.mp, self.maxMp));
+        ui.show(&format!("Strength: {}", self.strength));
+        ui.show(&format!("Defense: {}", self.defense));
+        ui.show(&format!("Agility: {}", self.agility));
+        ui.show(&format!("Intelligence: {}", self.intelligence));
+        if self.statPoints > 0 {
+            ui.show(&format!("Unspent stat points: {}", self.statPoints));
+        }
+        match content.rules.leveling.exp_to_level(self.level) {
+            Some(needed) => ui.show(&format!("Exp: {}/{} ({} to next level)", self.exp, needed, (needed - self.exp).max(0))),
+            None => ui.show(&format!("Exp: {} (level cap reached)", self.exp)),
+        }
+        ui.show("-------------------------");
+    }
+
+    pub fn take_Damage(&mut self, ui: &mut dyn Frontend, damage: i32) { // Make the player take damage
+        self.hp = (self.hp - damage).max(0);
+        ui.show(&format!("{} has taken {} damage! {} has {} hp remaining!", self.name, damage, self.name, self.hp));
+    }
+
+    pub fn gain_health(&mut self, ui: &mut dyn Frontend, health: i32) { // Make the player gain health
+        if self.hp + health > self.maxHp {
+            self.hp = self.maxHp;
+            ui.show(&format!("{} has gained {} hp! {} has max hp!", self.name, health, self.name));
+        }
+        else {
+            self.hp += health;
+            ui.show(&format!("{} has gained {} hp!", self.name, health));
+        }
+    }
+
+    pub fn remove_Mp(&mut self, ui: &mut dyn Frontend, mp: i32) { // Make the player lose mp
+        self.mp -= mp;
+        ui.show(&format!("{} has lost {} mp! {} has {} mp remaining!", self.name, mp, self.name, self.mp));
+    }
+
+    pub fn gain_Mp(&mut self, ui: &mut dyn Frontend, mp: i32) { // Make the player gain mp
+        if self.mp + mp > self.maxMp {
+            self.mp = self.maxMp;
+            ui.show(&format!("{} has gained {} mp! {} has max mp!", self.name, mp, self.name));
+        }
+        else {
+            self.mp += mp;
+            ui.show(&format!("{} has gained {} mp!", self.name, mp));
+        }
+    }
+
+    pub fn gain_Exp(&mut self, ui: &mut dyn Frontend, exp: i32) { // Make the player gain exp
+        self.exp += exp;
+        ui.show(&format!("{} has gained {} exp!", self.name, exp));
+    }
+
+    pub fn level_up(&mut self, class: Class, growth: &Growth) { // Applies one level of a class's growth
+        self.level += 1;
+        self.strength += growth.strength;
+        self.defense += growth.defense;
+        self.maxHp += growth.maxHp;
+        self.maxMp += growth.maxMp;
+        self.agility += growth.agility;
+        self.intelligence += growth.intelligence;
+        if class != self.class && !self.extraClasses.contains(&class) {
+            self.extraClasses.push(class);
+        }
+    }
+
+    pub fn class_name(&self) -> String { // e.g. "Warrior" or, after multiclassing, "Warrior/Mage"
+        std::iter::once(self.class).chain(self.extraClasses.iter().copied()).map(|c| c.name()).collect::<Vec<_>>().join("/")
+    }
+
+    // Whether a weapon or spell limited to these classes is open to the player
+    pub fn can_use(&self, classes: &[Class]) -> bool {
+        classes.is_empty() || classes.contains(&self.class) || self.extraClasses.iter().any(|c| classes.contains(c))
+    }
+
+    pub fn gain_Gold(&mut self, ui: &mut dyn Frontend, gold: i32) { // Make the player gain gold
+        self.gold += gold;
+        ui.show(&format!("{} has gained {} gold!", self.name, gold));
+    }
+
+    pub fn respawn(&mut self, ui: &mut dyn Frontend, defeat: &Defeat) { // Brings the player back at the shrine after losing a fight
+        let lost = self.gold * defeat.goldLossPercent / 100;
+        self.gold -= lost;
+        self.hp = (self.maxHp * defeat.respawnHpPercent / 100).max(1);
+        ui.show(&format!("{} wakes up at the shrine with {} hp, {} gold lighter.", self.name, self.hp, lost));
+    }
+
+    pub fn regenerate(&mut self, ui: &mut dyn Frontend, regen: &Regen) { // Slow recovery between fights
+        if regen.hpPerFight > 0 && self.hp < self.maxHp {
+            self.gain_health(ui, regen.hpPerFight.min(self.maxHp - self.hp));
+        }
+        if regen.mpPerFight > 0 && self.mp < self.maxMp {
+            self.gain_Mp(ui, regen.mpPerFight.min(self.maxMp - self.mp));
+        }
+    }
+
+    pub fn add_item(&mut self, ui: &mut dyn Frontend, item: &Item) -> bool {
+        match self.inventory.add(item) {
+            Ok(()) => {
+                ui.show(&format!("{} has added {} to their inventory!", self.name, item.name));
+                true
+            }
+            Err(message) => {
+                ui.show(&message);
+                false
+            }
+        }
+    }
+
+    pub fn remove_item(&mut self, id: &str) -> bool {
+        self.inventory.remove(id)
+    }
+
+    pub fn use_item(&mut self, ui: &mut dyn Frontend, item: &str, content: &Content, inCombat: bool) -> ItemUse {
+        // Items are typed by name, but the inventory only knows their ids
+        let held = self.inventory.iter()
+            .map(|(id, _)| id)
+            .find(|id| id.eq_ignore_ascii_case(item) || content.item(id).is_some_and(|i| i.name.eq_ignore_ascii_case(item)))
+            .map(str::to_string);
+        let id = match held {
+            Some(id) => id,
+            None => {
+                ui.show(&format!("{} does not have {} in their inventory!", self.name, item));
+                return ItemUse::NotUsed;
+            }
+        };
+        let item = match content.item(&id) {
+            Some(item) => item.clone(),
+            None => {
+                ui.show(&format!("{} has no idea how to use {}!", self.name, id));
+                return ItemUse::NotUsed;
+            }
+        };
+        let escapes = item.effects.iter().any(|e| matches!(e, Effect::Escape));
+        if escapes && !inCombat {
+            ui.show("There is nothing to escape from!");
+            return ItemUse::NotUsed;
+        }
+        // Statuses only last for a fight, so outside of one they would be wasted
+        if !inCombat && item.effects.iter().any(|e| matches!(e, Effect::ApplyStatus(_) | Effect::CureStatus(_))) {
+            ui.show(&format!("{} can only be used in a fight!", item.name));
+            return ItemUse::NotUsed;
+        }
+        self.remove_item(&id);
+        ui.show(&format!("{} has used {}!", self.name, item.name));
+        for effect in &item.effects {
+            match effect {
+                Effect::Heal(health) => self.gain_health(ui, *health),
+                Effect::RestoreMp(mp) => self.gain_Mp(ui, *mp),
+                Effect::Buff { stat, amount, turns } => {
+                    self.buffs.push(Buff { stat: *stat, amount: *amount, turnsLeft: *turns });
+                    ui.show(&format!("{}'s {} has changed by {} for {} turns!", self.name, stat.name(), amount, turns));
+                }
+                Effect::Cure => {
+                    self.buffs.retain(|b| b.amount >= 0);
+                    self.statuses.cure_harmful();
+                    ui.show(&format!("{} feels cleansed!", self.name));
+                }
+                Effect::ApplyStatus(apply) => {
+                    self.statuses.apply(apply, &[]);
+                    ui.show(&format!("{} is {}!", self.name, apply.kind.name()));
+                }
+                Effect::CureStatus(kinds) => {
+                    self.statuses.cure(kinds);
+                    let names: Vec<&str> = kinds.iter().map(|k| k.name()).collect();
+                    ui.show(&format!("{} is no longer {}!", self.name, names.join(" or ")));
+                }
+                Effect::Escape => (),
+            }
+        }
+        if escapes { ItemUse::Escaped } else { ItemUse::Used }
+    }
+
+    pub fn stat(&self, stat: Stat) -> i32 { // A stat including any active buffs
+        let base = match stat {
+            Stat::Strength => self.strength,
+            Stat::Defense => self.defense,
+            Stat::Agility => self.agility,
+            Stat::Intelligence => self.intelligence,
+        };
+        base + self.buffs.iter().filter(|b| b.stat == stat).map(|b| b.amount).sum::<i32>()
+    }
+
+    pub fn tick_buffs(&mut self, ui: &mut dyn Frontend) { // Called at the end of every combat turn
+        for buff in &mut self.buffs {
+            buff.turnsLeft -= 1;
+            if buff.turnsLeft <= 0 {
+                ui.show(&format!("{}'s {} is back to normal!", self.name, buff.stat.name()));
+            }
+        }
+        self.buffs.retain(|b| b.turnsLeft > 0);
+    }
+
+    pub fn show_inventory(&self, ui: &mut dyn Frontend, content: &Content) {
+        ui.clear();
+        ui.show("-------------------------");
+        ui.show(&format!("Equipped weapon: {} - Attack range Min: {} | Max: {}", self.equippedWeapon.name, self.equippedWeapon.minDamage, self.equippedWeapon.maxDamage));
+        ui.show(&format!("Gold: {}", self.gold));
+        ui.show(&format!("{}'s inventory:", self.name));
+        for (id, count) in self.inventory.iter() {
+            let name = content.item(id).map_or(id, |i| i.name.as_str());
+            ui.show(&format!("{} x{}", name, count));
+        }
+        ui.show(&format!("Carrying {}/{} items", self.inventory.total(), CARRY_LIMIT));
+        ui.show("-------------------------");
+    }
+
+    pub fn show_spellList(&self, ui: &mut dyn Frontend, content: &Content) {
+        ui.clear();
+        ui.show("-------------------------");
+        ui.show(&format!("{}'s spell list:", self.name));
+        for saved in &self.spellInventory {
+            // Shown as it will be cast, which is the content's version of the spell
+            let spell = content.spell(&saved.name).unwrap_or(saved);
+            let element = spell.element.map(|e| format!(" {}", e.name())).unwrap_or_default();
+            let target = if spell.area { " to every enemy" } else { "" };
+            ui.show(&format!("{} - {} - MP Cost: {} - Damage: {}{}{}", spell.name, spell.description, spell_cost(spell, self), spell.damage + self.stat(Stat::Intelligence), element, target));
+        }
+        ui.show("-------------------------");
+    }
+}
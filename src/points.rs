@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::Frontend;
+use crate::player::Player;
+use crate::rules::StatPoints;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Attribute {
+    // Everything stat points can be spent on
+    Strength,
+    Defense,
+    MaxHp,
+    MaxMp,
+    Agility,
+    Intelligence,
+}
+
+impl Attribute {
+    pub const ALL: [Attribute; 6] = [Attribute::Strength, Attribute::Defense, Attribute::MaxHp, Attribute::MaxMp, Attribute::Agility, Attribute::Intelligence];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Attribute::Strength => "strength",
+            Attribute::Defense => "defense",
+            Attribute::MaxHp => "hp",
+            Attribute::MaxMp => "mp",
+            Attribute::Agility => "agility",
+            Attribute::Intelligence => "intelligence",
+        }
+    }
+
+    // How much of the attribute one point buys
+    pub fn per_point(&self, rules: &StatPoints) -> i32 {
+        match self {
+            Attribute::MaxHp => rules.hpPerPoint,
+            Attribute::MaxMp => rules.mpPerPoint,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+pub struct Allocation {
+    // Points spent on each attribute since the last respec
+    pub strength: i32,
+    pub defense: i32,
+    pub maxHp: i32,
+    pub maxMp: i32,
+    pub agility: i32,
+    pub intelligence: i32,
+}
+
+impl Allocation {
+    fn points(&mut self, attribute: Attribute) -> &mut i32 {
+        match attribute {
+            Attribute::Strength => &mut self.strength,
+            Attribute::Defense => &mut self.defense,
+            Attribute::MaxHp => &mut self.maxHp,
+            Attribute::MaxMp => &mut self.maxMp,
+            Attribute::Agility => &mut self.agility,
+            Attribute::Intelligence => &mut self.intelligence,
+        }
+    }
+
+    pub fn total(&self) -> i32 {
+        self.strength + self.defense + self.maxHp + self.maxMp + self.agility + self.intelligence
+    }
+}
+
+impl Player {
+    fn attribute(&mut self, attribute: Attribute) -> &mut i32 {
+        match attribute {
+            Attribute::Strength => &mut self.strength,
+            Attribute::Defense => &mut self.defense,
+            Attribute::MaxHp => &mut self.maxHp,
+            Attribute::MaxMp => &mut self.maxMp,
+            Attribute::Agility => &mut self.agility,
+            Attribute::Intelligence => &mut self.intelligence,
+        }
+    }
+
+    pub fn allocate(&mut self, attribute: Attribute, points: i32, rules: &StatPoints) -> Result<(), String> {
+        if points < 1 {
+            return Err("You have to spend at least 1 point!".to_string());
+        }
+        if points > self.statPoints {
+            return Err(format!("You only have {} points to spend!", self.statPoints));
+        }
+        self.statPoints -= points;
+        *self.allocated.points(attribute) += points;
+        *self.attribute(attribute) += points * attribute.per_point(rules);
+        Ok(())
+    }
+
+    // Takes back every spent point, so they can be spent again
+    pub fn respec(&mut self, ui: &mut dyn Frontend, rules: &StatPoints) {
+        for attribute in Attribute::ALL {
+            let points = std::mem::take(self.allocated.points(attribute));
+            *self.attribute(attribute) -= points * attribute.per_point(rules);
+            self.statPoints += points;
+        }
+        self.hp = self.hp.min(self.maxHp);
+        self.mp = self.mp.min(self.maxMp);
+        ui.show(&format!("{} has {} stat points to spend again!", self.name, self.statPoints));
+    }
+}
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Rules {
+    // Game-wide settings read from rules.json, for things that aren't a list of content
+    pub autosave: Autosave,
+    pub defeat: Defeat,
+    pub inn: Inn,
+    // Whether the shrine lets a character take levels in classes other than their own
+    pub multiclass: bool,
+    pub leveling: Leveling,
+    pub statPoints: StatPoints,
+    pub regen: Regen,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Autosave {
+    // When the game saves without being asked
+    pub afterFight: bool,
+    pub afterPurchase: bool,
+    pub afterLevelUp: bool,
+    pub onQuit: bool,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Defeat {
+    // What losing a fight costs the player
+    pub goldLossPercent: i32,
+    // The player wakes up at the shrine with this much of their max hp
+    pub respawnHpPercent: i32,
+    // Losing a fight deletes the save and ends the game
+    pub permadeath: bool,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Inn {
+    // Gold for a night that restores all hp and mp
+    pub price: i32,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Regen {
+    // Recovered after every fight the player walks away from, 0 turns it off
+    pub hpPerFight: i32,
+    pub mpPerFight: i32,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct StatPoints {
+    // Points granted by a level up at the shrine taken instead of a class's fixed growth
+    pub perLevel: i32,
+    pub hpPerPoint: i32,
+    pub mpPerPoint: i32,
+    // Gold to take back every spent point
+    pub respecPrice: i32,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Leveling {
+    pub expCurve: ExpCurve,
+    // Nobody levels past this
+    pub levelCap: i32,
+    // Level up in the player's own class as soon as there is enough exp, instead of at the shrine
+    pub autoLevel: bool,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ExpCurve {
+    // Exp needed to leave each level, starting from level 0
+    Table(Vec<i32>),
+    // base + perLevel * level
+    Linear { base: i32, perLevel: i32 },
+}
+
+impl Leveling {
+    // Exp needed to go from this level to the next, None once there is no next level
+    pub fn exp_to_level(&self, level: i32) -> Option<i32> {
+        if level >= self.levelCap || level < 0 {
+            return None;
+        }
+        match &self.expCurve {
+            ExpCurve::Table(table) => table.get(level as usize).copied(),
+            ExpCurve::Linear { base, perLevel } => Some(base + perLevel * level),
+        }
+    }
+}
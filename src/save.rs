@@ -0,0 +1,421 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::player::Player;
+use crate::points::Allocation;
+
+// Bump this and add a migration to MIGRATIONS whenever the saved Player changes shape
+pub const CURRENT_VERSION: u32 = 5;
+
+// Upgrades a saved player by one version, in place
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+// MIGRATIONS[n] turns a version n + 1 player into a version n + 2 player
+const MIGRATIONS: &[Migration] = &[
+    migrate_1_to_2,
+    migrate_2_to_3,
+    migrate_3_to_4,
+    migrate_4_to_5,
+];
+
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    // What is written to disk: the player plus the version its fields follow
+    version: u32,
+    // Seconds since the unix epoch, for the character list
+    savedAt: u64,
+    player: &'a Player,
+}
+
+#[derive(Deserialize)]
+struct LoadedSaveFile {
+    version: u32,
+    #[serde(default)]
+    savedAt: Option<u64>,
+    player: Value,
+}
+
+pub struct SaveSummary {
+    // What the character list shows about a save without keeping the whole player around
+    pub name: String,
+    pub level: i32,
+    pub gold: i32,
+    pub savedAt: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    // The save file couldn't be read, written or moved
+    Io { path: PathBuf, source: io::Error },
+    // The save file exists but isn't a save we understand
+    Corrupt { path: PathBuf, source: serde_json::Error },
+    Serialize(serde_json::Error),
+    // The save was written by a newer version of the game
+    UnsupportedVersion { path: PathBuf, version: u32 },
+    // The save is readable but couldn't be brought up to date
+    Migration { path: PathBuf, version: u32, message: String },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            SaveError::Corrupt { path, source } => write!(f, "{} is not a readable save: {}", path.display(), source),
+            SaveError::Serialize(source) => write!(f, "failed to serialize player data: {}", source),
+            SaveError::UnsupportedVersion { path, version } => write!(f, "{} is save version {}, but this game only understands up to version {}", path.display(), version, CURRENT_VERSION),
+            SaveError::Migration { path, version, message } => write!(f, "{} could not be upgraded from save version {}: {}", path.display(), version, message),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Io { source, .. } => Some(source),
+            SaveError::Corrupt { source, .. } => Some(source),
+            SaveError::Serialize(source) => Some(source),
+            SaveError::UnsupportedVersion { .. } | SaveError::Migration { .. } => None,
+        }
+    }
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> SaveError + '_ {
+    move |source| SaveError::Io { path: path.to_path_buf(), source }
+}
+
+// Where saves go unless a flag or ZYVERIA_SAVE_DIR says otherwise, following the XDG base directory spec
+pub fn default_save_dir() -> PathBuf {
+    let data = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from));
+    match data {
+        Some(data) => data.join("zyveria").join("saves"),
+        None => PathBuf::from("saves"),
+    }
+}
+
+pub const RESERVED_NAMES: &[&str] = &["new", "new character", "delete", "duplicate", "rename", "quit", "back"];
+
+// Lists the old saves the player chose not to import, so they aren't offered again
+const DECLINED_IMPORTS: &str = "declined-imports.txt";
+
+pub fn declined_imports(dir: &Path) -> Vec<String> {
+    fs::read_to_string(dir.join(DECLINED_IMPORTS))
+        .map(|data| data.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+pub fn decline_imports(dir: &Path, names: &[&str]) -> Result<(), SaveError> {
+    fs::create_dir_all(dir).map_err(io_error(dir))?;
+    let mut declined = declined_imports(dir);
+    declined.extend(names.iter().map(|n| n.to_string()));
+    let path = dir.join(DECLINED_IMPORTS);
+    write_atomic(&path, (declined.join("\n") + "\n").as_bytes())
+}
+
+// Character names become file names, so only allow characters that are safe in one
+pub fn sanitize_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Your name can't be empty!".to_string());
+    }
+    if name.chars().count() > 24 {
+        return Err("Your name can be at most 24 characters long!".to_string());
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '\'')) {
+        return Err("Your name can only use letters, numbers, spaces, dashes, underscores and apostrophes!".to_string());
+    }
+    // The character menu reads these as commands
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(name)) {
+        return Err(format!("{} is a menu command, please pick another name!", name));
+    }
+    Ok(name.to_string())
+}
+
+pub fn save_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.json", name))
+}
+
+// The previous save is kept here every time the game saves over it
+pub fn backup_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.json.bak", name))
+}
+
+// Writes to a temporary file and renames it over the target, so a crash mid-write
+// leaves either the old file or the new one, never half of each
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), SaveError> {
+    let mut tmpName = path.as_os_str().to_owned();
+    tmpName.push(".tmp");
+    let tmp = PathBuf::from(tmpName);
+    let mut file = fs::File::create(&tmp).map_err(io_error(&tmp))?;
+    file.write_all(data).map_err(io_error(&tmp))?;
+    file.sync_all().map_err(io_error(&tmp))?;
+    drop(file);
+    fs::rename(&tmp, path).map_err(io_error(path))
+}
+
+impl Player {
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, SaveError> {
+        fs::create_dir_all(dir).map_err(io_error(dir))?;
+        let path = save_path(dir, &self.name);
+        let savedAt = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let serialized = serde_json::to_string(&SaveFile { version: CURRENT_VERSION, savedAt, player: self }).map_err(SaveError::Serialize)?;
+        if path.exists() {
+            let backup = backup_path(dir, &self.name);
+            fs::copy(&path, &backup).map_err(io_error(&backup))?;
+        }
+        write_atomic(&path, serialized.as_bytes())?;
+        Ok(path)
+    }
+
+    // Ok(None) means there is no save for this name, an unreadable save is an error
+    pub fn load(dir: &Path, name: &str) -> Result<Option<Self>, SaveError> {
+        let path = save_path(dir, name);
+        let data = match fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(SaveError::Io { path, source }),
+        };
+        parse_save(&data).map(Some).map_err(|e| e.at(&path))
+    }
+}
+
+// Reads a save of any known version and brings it up to CURRENT_VERSION
+pub fn parse_save(data: &str) -> Result<Player, SaveError> {
+    parse_save_file(data).map(|(player, _)| player)
+}
+
+fn parse_save_file(data: &str) -> Result<(Player, Option<u64>), SaveError> {
+    let corrupt = |source| SaveError::Corrupt { path: PathBuf::new(), source };
+    let value: Value = serde_json::from_str(data).map_err(corrupt)?;
+    // Saves from before the envelope are a bare player, which is version 1
+    let (version, savedAt, player) = if value.get("version").is_some() {
+        let file: LoadedSaveFile = serde_json::from_value(value).map_err(corrupt)?;
+        (file.version, file.savedAt, file.player)
+    } else {
+        (1, None, value)
+    };
+    if version == 0 || version > CURRENT_VERSION {
+        return Err(SaveError::UnsupportedVersion { path: PathBuf::new(), version });
+    }
+    let mut player = match player {
+        Value::Object(player) => player,
+        _ => return Err(SaveError::Migration { path: PathBuf::new(), version, message: "the player is not an object".to_string() }),
+    };
+    for (from, migrate) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+        migrate(&mut player).map_err(|message| SaveError::Migration { path: PathBuf::new(), version: from as u32 + 1, message })?;
+    }
+    let player = serde_json::from_value(Value::Object(player)).map_err(corrupt)?;
+    Ok((player, savedAt))
+}
+
+// Every character in the save directory, by name; saves that can't be read are left out
+pub fn list_saves(dir: &Path) -> Vec<SaveSummary> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut saves: Vec<SaveSummary> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let data = fs::read_to_string(&path).ok()?;
+            let (player, savedAt) = parse_save_file(&data).ok()?;
+            Some(SaveSummary { name: player.name, level: player.level, gold: player.gold, savedAt })
+        })
+        .collect();
+    saves.sort_by_key(|s| s.name.to_lowercase());
+    saves
+}
+
+// Moves a save, and its backup if there is one, into another directory without
+// touching a save that is already there
+pub fn import_save(from: &Path, to: &Path, name: &str) -> Result<(), SaveError> {
+    let target = save_path(to, name);
+    if target.exists() {
+        return Err(SaveError::Io { path: target, source: io::Error::from(io::ErrorKind::AlreadyExists) });
+    }
+    fs::create_dir_all(to).map_err(io_error(to))?;
+    let source = save_path(from, name);
+    fs::copy(&source, &target).map_err(io_error(&source))?;
+    let backup = backup_path(from, name);
+    if backup.exists() {
+        let backupTarget = backup_path(to, name);
+        fs::copy(&backup, &backupTarget).map_err(io_error(&backupTarget))?;
+    }
+    delete_save(from, name)
+}
+
+pub fn delete_save(dir: &Path, name: &str) -> Result<(), SaveError> {
+    let path = save_path(dir, name);
+    fs::remove_file(&path).map_err(io_error(&path))?;
+    let backup = backup_path(dir, name);
+    if backup.exists() {
+        fs::remove_file(&backup).map_err(io_error(&backup))?;
+    }
+    Ok(())
+}
+
+// Saves a copy of a character under a new name, leaving the original alone
+pub fn copy_save(dir: &Path, from: &str, to: &str) -> Result<(), SaveError> {
+    let mut player = Player::load(dir, from)?.ok_or_else(|| SaveError::Io {
+        path: save_path(dir, from),
+        source: io::Error::from(io::ErrorKind::NotFound),
+    })?;
+    player.name = to.to_string();
+    player.save(dir)?;
+    Ok(())
+}
+
+pub fn rename_save(dir: &Path, from: &str, to: &str) -> Result<(), SaveError> {
+    copy_save(dir, from, to)?;
+    delete_save(dir, from)
+}
+
+// Formats a savedAt timestamp as a UTC date and time, e.g. 2026-10-18 14:03
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let minutes = (secs % 86_400) / 60;
+    // Howard Hinnant's days-to-civil algorithm
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, minutes / 60, minutes % 60)
+}
+
+impl SaveError {
+    // parse_save doesn't know which file it is reading, so load fills the path in afterwards
+    fn at(self, path: &Path) -> Self {
+        let path = path.to_path_buf();
+        match self {
+            SaveError::Io { source, .. } => SaveError::Io { path, source },
+            SaveError::Corrupt { source, .. } => SaveError::Corrupt { path, source },
+            SaveError::UnsupportedVersion { version, .. } => SaveError::UnsupportedVersion { path, version },
+            SaveError::Migration { version, message, .. } => SaveError::Migration { path, version, message },
+            SaveError::Serialize(source) => SaveError::Serialize(source),
+        }
+    }
+}
+
+// Version 1 kept one inventory entry per item name, e.g. ["Herb", "Herb", "Mana Stone"].
+// Version 2 counts stacks by item id, and item ids are the old names in snake case.
+fn migrate_1_to_2(player: &mut Map<String, Value>) -> Result<(), String> {
+    let Some(Value::Array(names)) = player.get("inventory") else {
+        return Ok(());
+    };
+    let mut stacks = Map::new();
+    for name in names {
+        let name = name.as_str().ok_or("inventory entries must be item names")?;
+        let id = name.trim().to_lowercase().replace(' ', "_");
+        let count = stacks.get(&id).and_then(Value::as_u64).unwrap_or(0);
+        stacks.insert(id, Value::from(count + 1));
+    }
+    player.insert("inventory".to_string(), Value::Object(stacks));
+    Ok(())
+}
+
+// Version 3 added classes. Older characters picked a class at every level, so they become
+// whichever class their stats lean towards most: defense for warriors, strength for
+// barbarians and mp for mages, with warrior breaking ties.
+// Version 2 and older also let hp drop below 0 and saved it that way, which would start
+// the next fight already lost, so those characters come back with at least 1 hp.
+fn migrate_2_to_3(player: &mut Map<String, Value>) -> Result<(), String> {
+    let stat = |name: &str| player.get(name).and_then(Value::as_i64).ok_or(format!("{} must be a number", name));
+    let hp = stat("hp")?.clamp(1, stat("maxHp")?.max(1));
+    let (strength, defense, mpGained) = (stat("strength")?, stat("defense")?, stat("maxMp")? - 5);
+    let class = if mpGained > defense && mpGained > strength {
+        "mage"
+    } else if strength > defense {
+        "barbarian"
+    } else {
+        "warrior"
+    };
+    player.insert("class".to_string(), Value::from(class));
+    player.insert("hp".to_string(), Value::from(hp));
+    Ok(())
+}
+
+// Version 4 added agility, intelligence and stat points, which older characters never had
+fn migrate_3_to_4(player: &mut Map<String, Value>) -> Result<(), String> {
+    for field in ["agility", "intelligence", "statPoints"] {
+        player.insert(field.to_string(), Value::from(0));
+    }
+    let allocated = serde_json::to_value(Allocation::default()).map_err(|e| e.to_string())?;
+    player.insert("allocated".to_string(), allocated);
+    Ok(())
+}
+
+// Version 5 keeps shop stock in the save; older characters find every shop fully stocked
+fn migrate_4_to_5(player: &mut Map<String, Value>) -> Result<(), String> {
+    player.insert("shopStock".to_string(), Value::Object(Map::new()));
+    Ok(())
+}
+
+// path itself, or path.1, path.2 and so on, whichever nothing is using yet
+fn unused_path(path: PathBuf) -> PathBuf {
+    let mut candidate = path.clone();
+    let mut n = 1;
+    while candidate.exists() {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        candidate = PathBuf::from(name);
+        n += 1;
+    }
+    candidate
+}
+
+// Moves an unreadable save out of the way so a new character can't overwrite it,
+// never over a save quarantined before
+pub fn quarantine(dir: &Path, name: &str) -> Result<PathBuf, SaveError> {
+    let path = save_path(dir, name);
+    let target = unused_path(dir.join(format!("{}.json.corrupt", name)));
+    fs::rename(&path, &target).map_err(io_error(&path))?;
+    Ok(target)
+}
+
+// A new character's second save would copy over the backup, so starting over keeps it aside too
+pub fn quarantine_backup(dir: &Path, name: &str) -> Result<Option<PathBuf>, SaveError> {
+    let backup = backup_path(dir, name);
+    if !backup.exists() {
+        return Ok(None);
+    }
+    let target = unused_path(dir.join(format!("{}.json.bak.kept", name)));
+    fs::rename(&backup, &target).map_err(io_error(&backup))?;
+    Ok(Some(target))
+}
+
+// Ok(None) means there is no backup to fall back on
+pub fn load_backup(dir: &Path, name: &str) -> Result<Option<Player>, SaveError> {
+    let path = backup_path(dir, name);
+    let data = match fs::read_to_string(&path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(SaveError::Io { path, source }),
+    };
+    parse_save(&data).map(Some).map_err(|e| e.at(&path))
+}
+
+// Puts the backup back in place of an unreadable save, returns where the unreadable one went
+pub fn restore_backup(dir: &Path, name: &str) -> Result<PathBuf, SaveError> {
+    let quarantined = quarantine(dir, name)?;
+    let backup = backup_path(dir, name);
+    fs::copy(&backup, save_path(dir, name)).map_err(io_error(&backup))?;
+    Ok(quarantined)
+}
@@ -0,0 +1,267 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::class::Class;
+use crate::content::Content;
+use crate::frontend::Frontend;
+use crate::player::{Player, Weapon};
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum Goods {
+    // What a stock entry sells, by item id or by weapon and spell name
+    Item(String),
+    Weapon(String),
+    Spell(String),
+}
+
+#[derive(Deserialize, Clone)]
+pub struct StockEntry {
+    #[serde(flatten)]
+    pub goods: Goods,
+    pub price: i32,
+    // How much the shop pays when the player sells one back, half the price if not given
+    pub sellPrice: Option<i32>,
+    // How many the shop has before it sells out, unlimited if not given
+    pub limit: Option<u32>,
+    // How many fights until a sold out entry is back in stock, never if not given
+    pub restock: Option<u32>,
+    #[serde(skip)]
+    pub state: StockState,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct StockState {
+    // How far an entry has been bought down, kept in the save so limits outlast the session
+    pub sold: u32,
+    pub fightsSinceSale: u32,
+}
+
+impl StockEntry {
+    // The item id, weapon name or spell name this entry refers to
+    pub fn key(&self) -> &str {
+        match &self.goods {
+            Goods::Item(key) | Goods::Weapon(key) | Goods::Spell(key) => key,
+        }
+    }
+
+    pub fn name<'a>(&'a self, content: &'a Content) -> &'a str {
+        match &self.goods {
+            Goods::Item(id) => content.item(id).map_or(id.as_str(), |i| i.name.as_str()),
+            _ => self.key(),
+        }
+    }
+
+    pub fn remaining(&self) -> Option<u32> {
+        self.limit.map(|limit| limit.saturating_sub(self.state.sold))
+    }
+
+    pub fn sell_price(&self) -> i32 {
+        self.sellPrice.unwrap_or(self.price / 2)
+    }
+
+    fn describe(&self, content: &Content) -> String {
+        match &self.goods {
+            Goods::Item(id) => content.item(id).map(|i| i.description.clone()).unwrap_or_default(),
+            Goods::Weapon(name) => content.weapon(name)
+                .map(|w| format!("Increases attack range to {}-{}{}", w.minDamage, w.maxDamage, class_note(&w.classes)))
+                .unwrap_or_default(),
+            Goods::Spell(name) => content.spell(name)
+                .map(|s| {
+                    let element = s.element.map(|e| format!(" {}", e.name())).unwrap_or_default();
+                    let target = if s.area { " to every enemy" } else { "" };
+                    format!("Deals {}{} damage{}, costs {} mp{}", s.damage, element, target, s.mpCost, class_note(&s.classes))
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn class_note(classes: &[Class]) -> String {
+    if classes.is_empty() {
+        return String::new();
+    }
+    format!(", {} only", classes.iter().map(|c| c.name()).collect::<Vec<_>>().join("/"))
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Shop {
+    // A vendor and everything it trades in
+    pub name: String,
+    pub greeting: String,
+    pub stock: Vec<StockEntry>,
+}
+
+impl Shop {
+    // Returns whether anything was bought or sold
+    pub fn visit(&mut self, ui: &mut dyn Frontend, player: &mut Player, content: &Content) -> bool {
+        ui.clear();
+        ui.show(&self.greeting);
+        let mut options = Vec::new();
+        for entry in &self.stock {
+            let description = entry.describe(content);
+            options.push(match entry.remaining() {
+                Some(0) => format!("> {} - Sold out", entry.name(content)),
+                Some(remaining) => format!("> {} - {} gold ({}) [{} left]", entry.name(content), entry.price, description, remaining),
+                None => format!("> {} - {} gold ({})", entry.name(content), entry.price, description),
+            });
+        }
+        if self.buys_anything() {
+            options.push("> Sell - Sell something back to the shop.".to_string());
+        }
+        options.push("< Back - Return to the main menu.".to_string());
+        let input = ui.menu("What would you like to buy?", &options);
+        let input = input.as_str();
+        match input {
+            "quit" | "Quit" | "back" | "Back" => false,
+            "sell" | "Sell" if self.buys_anything() => self.sell_menu(ui, player, content),
+            _ => match self.stock.iter().position(|e| e.name(content).eq_ignore_ascii_case(input)) {
+                Some(index) => match self.buy(ui, index, player, content) {
+                    Ok(()) => true,
+                    Err(message) => {
+                        ui.show(&message);
+                        false
+                    }
+                },
+                None => {
+                    ui.show("Invalid item!");
+                    false
+                }
+            },
+        }
+    }
+
+    pub fn buy(&mut self, ui: &mut dyn Frontend, index: usize, player: &mut Player, content: &Content) -> Result<(), String> {
+        let entry = &mut self.stock[index];
+        if entry.remaining() == Some(0) {
+            return Err(format!("{} is sold out!", entry.name(content)));
+        }
+        if player.gold < entry.price {
+            return Err(format!("You do not have enough gold to buy {}!", entry.name(content)));
+        }
+        match &entry.goods {
+            Goods::Item(id) => {
+                let item = content.item(id).ok_or(format!("The shop has run out of {}!", id))?;
+                player.inventory.check_add(item)?;
+                player.gain_Gold(ui, -entry.price);
+                player.add_item(ui, item);
+            }
+            Goods::Weapon(name) => {
+                let weapon = content.weapon(name).ok_or(format!("The shop has run out of {}!", name))?;
+                if !player.can_use(&weapon.classes) {
+                    return Err(format!("A {} can't wield the {}!", player.class_name(), weapon.name));
+                }
+                player.gain_Gold(ui, -entry.price);
+                player.equippedWeapon = weapon.clone();
+            }
+            Goods::Spell(name) => {
+                if player.spellInventory.iter().any(|s| s.name.eq_ignore_ascii_case(name)) {
+                    return Err(format!("You already know {}!", name));
+                }
+                let spell = content.spell(name).ok_or(format!("The shop has run out of {}!", name))?;
+                if !player.can_use(&spell.classes) {
+                    return Err(format!("A {} can't learn {}!", player.class_name(), spell.name));
+                }
+                player.gain_Gold(ui, -entry.price);
+                player.spellInventory.push(spell.clone());
+            }
+        }
+        entry.state.sold += 1;
+        entry.state.fightsSinceSale = 0;
+        ui.show(&format!("{} has bought {} for {} gold!", player.name, entry.name(content), entry.price));
+        Ok(())
+    }
+
+    pub fn sell(&mut self, ui: &mut dyn Frontend, index: usize, player: &mut Player, content: &Content) -> Result<(), String> {
+        let entry = &mut self.stock[index];
+        match &entry.goods {
+            Goods::Item(id) => {
+                if !player.remove_item(id) {
+                    return Err(format!("{} does not have {} in their inventory!", player.name, entry.name(content)));
+                }
+            }
+            Goods::Weapon(name) => {
+                if !player.equippedWeapon.name.eq_ignore_ascii_case(name) {
+                    return Err(format!("{} does not have {} equipped!", player.name, name));
+                }
+                player.equippedWeapon = Weapon::fist();
+            }
+            Goods::Spell(name) => return Err(format!("The shop does not buy back {}!", name)),
+        }
+        player.gain_Gold(ui, entry.sell_price());
+        // Anything sold back goes on the shelf again
+        entry.state.sold = entry.state.sold.saturating_sub(1);
+        ui.show(&format!("{} has sold {} for {} gold!", player.name, entry.name(content), entry.sell_price()));
+        Ok(())
+    }
+
+    // The stock worth saving, only entries something has been bought from
+    pub fn stock_state(&self) -> BTreeMap<String, StockState> {
+        self.stock.iter()
+            .filter(|e| e.state.sold > 0)
+            .map(|e| (e.key().to_string(), e.state))
+            .collect()
+    }
+
+    pub fn restore(&mut self, saved: &BTreeMap<String, StockState>) {
+        for entry in &mut self.stock {
+            if let Some(state) = saved.get(entry.key()) {
+                entry.state = *state;
+            }
+        }
+    }
+
+    // Called after every fight so sold out stock can come back
+    pub fn tick(&mut self) {
+        for entry in &mut self.stock {
+            if entry.state.sold == 0 {
+                continue;
+            }
+            if let Some(restock) = entry.restock {
+                entry.state.fightsSinceSale += 1;
+                if entry.state.fightsSinceSale >= restock {
+                    entry.state.sold = 0;
+                    entry.state.fightsSinceSale = 0;
+                }
+            }
+        }
+    }
+
+    fn buys_anything(&self) -> bool {
+        self.stock.iter().any(|e| !matches!(e.goods, Goods::Spell(_)))
+    }
+
+    fn sell_menu(&mut self, ui: &mut dyn Frontend, player: &mut Player, content: &Content) -> bool {
+        let mut options = Vec::new();
+        for entry in &self.stock {
+            let owned = match &entry.goods {
+                Goods::Item(id) => player.inventory.count(id) > 0,
+                Goods::Weapon(name) => player.equippedWeapon.name.eq_ignore_ascii_case(name),
+                Goods::Spell(_) => false,
+            };
+            if owned {
+                options.push(format!("> {} - {} gold", entry.name(content), entry.sell_price()));
+            }
+        }
+        options.push("< Back - Return to the shop.".to_string());
+        let input = ui.menu("What would you like to sell?", &options);
+        let input = input.as_str();
+        if matches!(input, "quit" | "Quit" | "back" | "Back") {
+            return false;
+        }
+        match self.stock.iter().position(|e| e.name(content).eq_ignore_ascii_case(input)) {
+            Some(index) => match self.sell(ui, index, player, content) {
+                Ok(()) => true,
+                Err(message) => {
+                    ui.show(&message);
+                    false
+                }
+            },
+            None => {
+                ui.show("Invalid item!");
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::class::Class;
+use crate::content::Content;
+use crate::frontend::Frontend;
+use crate::player::Player;
+use crate::points::Attribute;
+
+pub fn shrine(ui: &mut dyn Frontend, player: &mut Player, content: &Content) -> bool { // Returns whether the player leveled up or changed their stats
+    ui.clear();
+    let cost = content.rules.leveling.exp_to_level(player.level);
+    let points = &content.rules.statPoints;
+    // Without multiclassing a character only ever grows in their own class
+    let classes: Vec<Class> = if content.rules.multiclass {
+        Class::ALL.to_vec()
+    } else {
+        vec![player.class]
+    };
+    ui.show("-------------------------");
+    ui.show(&format!("What services would you like to use? (You have {} exp)", player.exp));
+    ui.show(&format!("Current Stats: Level {}, Strength {}, Defense {}, Agility {}, Intelligence {}, Hp{}/{}, Mp{}/{}", player.level, player.strength, player.defense, player.agility, player.intelligence, player.hp, player.maxHp, player.mp, player.maxMp));
+    ui.show("");
+    match cost {
+        Some(cost) => {
+            for class in &classes {
+                ui.show(&format!("> Level Up as a {} - {} exp", class.name(), cost));
+                ui.show(&format!("Level {}: {}", player.level + 1, content.class(*class).growth.preview(player)));
+                ui.show("");
+            }
+            ui.show(&format!("> Level Up with Points - {} exp", cost));
+            ui.show(&format!("Level {}: +{} stat points to spend as you like", player.level + 1, points.perLevel));
+            ui.show("");
+        }
+        None => {
+            ui.show(&format!("{} has reached the level cap of {}!", player.name, player.level));
+            ui.show("");
+        }
+    }
+    if player.statPoints > 0 {
+        ui.show(&format!("> Spend Points - {} unspent", player.statPoints));
+    }
+    if player.allocated.total() > 0 {
+        ui.show(&format!("> Respec - {} gold, take back every spent point", points.respecPrice));
+    }
+    ui.show("< Back - Return to the main menu.");
+    ui.show("-------------------------");
+    let input = ui.prompt("");
+    let service = input.strip_prefix("Level Up as a ").or_else(|| input.strip_prefix("Level ")).or_else(|| input.strip_prefix("level ")).unwrap_or(&input);
+    match service {
+        "quit" | "Quit" | "back" | "Back" => false,
+        "spend" | "Spend" | "spend points" | "Spend Points" if player.statPoints > 0 => spend_points(ui, player, content),
+        "respec" | "Respec" if player.allocated.total() > 0 => {
+            if player.gold < points.respecPrice {
+                ui.show("You do not have enough gold to respec!");
+                return false;
+            }
+            player.gain_Gold(ui, -points.respecPrice);
+            player.respec(ui, points);
+            spend_points(ui, player, content);
+            true
+        }
+        _ => {
+            let Some(cost) = cost else {
+                ui.show("Invalid service!");
+                return false;
+            };
+            let withPoints = matches!(service, "points" | "Points" | "Up with Points" | "up with points");
+            let class = classes.into_iter().find(|c| c.name().eq_ignore_ascii_case(service));
+            if class.is_none() && !withPoints {
+                ui.show("Invalid service!");
+                return false;
+            }
+            if player.exp < cost {
+                ui.show("You do not have enough exp to level up!");
+                return false;
+            }
+            match class {
+                Some(class) => level_up(ui, player, class, cost, content),
+                None => {
+                    player.gain_Exp(ui, -cost);
+                    player.level += 1;
+                    player.statPoints += points.perLevel;
+                    ui.show(&format!("{} has leveled up to level {} and has {} stat points to spend!", player.name, player.level, player.statPoints));
+                    spend_points(ui, player, content);
+                }
+            }
+            true
+        }
+    }
+}
+
+// Lets the player spend points until they run out or go back, returns whether any were spent
+fn spend_points(ui: &mut dyn Frontend, player: &mut Player, content: &Content) -> bool {
+    let rules = &content.rules.statPoints;
+    let mut spent = false;
+    while player.statPoints > 0 {
+        let options: Vec<String> = Attribute::ALL.iter()
+            .map(|a| format!("> {} - +{} per point", a.name(), a.per_point(rules)))
+            .collect();
+        let input = ui.menu(&format!("Spend your points ({} left), or go back to keep them for later:", player.statPoints), &options);
+        if matches!(input.as_str(), "quit" | "Quit" | "back" | "Back") {
+            break;
+        }
+        // Either "strength" for a single point or "strength 2"
+        let (name, count) = match input.rsplit_once(' ').and_then(|(name, count)| Some((name, count.parse::<i32>().ok()?))) {
+            Some(pair) => pair,
+            None => (input.as_str(), 1),
+        };
+        let Some(attribute) = Attribute::ALL.into_iter().find(|a| a.name().eq_ignore_ascii_case(name)) else {
+            ui.show("Invalid stat!");
+            continue;
+        };
+        match player.allocate(attribute, count, rules) {
+            Ok(()) => {
+                ui.show(&format!("{} has put {} points into {}!", player.name, count, attribute.name()));
+                spent = true;
+            }
+            Err(message) => ui.show(&message),
+        }
+    }
+    spent
+}
+
+fn level_up(ui: &mut dyn Frontend, player: &mut Player, class: Class, cost: i32, content: &Content) {
+    player.gain_Exp(ui, -cost);
+    player.level_up(class, &content.class(class).growth);
+    ui.show(&format!("{} has leveled up to level {}!", player.name, player.level));
+}
+
+// Levels the player up in their own class for as long as their exp allows, returns whether they leveled
+pub fn auto_level(ui: &mut dyn Frontend, player: &mut Player, content: &Content) -> bool {
+    let mut leveled = false;
+    while let Some(cost) = content.rules.leveling.exp_to_level(player.level) {
+        if player.exp < cost {
+            break;
+        }
+        level_up(ui, player, player.class, cost, content);
+        leveled = true;
+    }
+    leveled
+}
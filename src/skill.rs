@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::class::Class;
+use crate::content::Content;
+use crate::frontend::Frontend;
+use crate::player::Player;
+
+#[derive(Deserialize, Clone)]
+pub struct Skill {
+    // A fighting technique, free to use but needing time to recover
+    pub name: String,
+    pub description: String,
+    pub effect: SkillEffect,
+    // Player turns before it can be used again
+    pub cooldown: i32,
+    // Who knows it, anyone if empty
+    #[serde(default)]
+    pub classes: Vec<Class>,
+    // The weapons it is done with, any if empty
+    #[serde(default)]
+    pub weapons: Vec<String>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum SkillEffect {
+    // hits attacks on one enemy, each at powerPercent of a normal attack
+    Strike { hits: i32, powerPercent: i32 },
+    // Goads every enemy into plain attacks for a few of their turns
+    Taunt { turns: i32 },
+}
+
+impl Skill {
+    pub fn usable_by(&self, player: &Player) -> bool {
+        player.can_use(&self.classes) && (self.weapons.is_empty() || self.weapons.contains(&player.equippedWeapon.name))
+    }
+}
+
+// The skills the player's classes and weapon give them right now
+pub fn known<'a>(content: &'a Content, player: &Player) -> Vec<&'a Skill> {
+    content.skills.iter().filter(|s| s.usable_by(player)).collect()
+}
+
+#[derive(Default)]
+pub struct Cooldowns(BTreeMap<String, i32>);
+
+impl Cooldowns {
+    pub fn start(&mut self, skill: &Skill) {
+        if skill.cooldown > 0 {
+            self.0.insert(skill.name.clone(), skill.cooldown);
+        }
+    }
+
+    // Turns until the skill is ready, 0 if it is
+    pub fn remaining(&self, skill: &Skill) -> i32 {
+        self.0.get(&skill.name).copied().unwrap_or(0)
+    }
+
+    // Called at the start of each of the player's turns
+    pub fn tick(&mut self) {
+        self.0.values_mut().for_each(|turns| *turns -= 1);
+        self.0.retain(|_, turns| *turns > 0);
+    }
+}
+
+pub fn show_skills(ui: &mut dyn Frontend, skills: &[&Skill], cooldowns: &Cooldowns) {
+    ui.show("Skills:");
+    for skill in skills {
+        let ready = match cooldowns.remaining(skill) {
+            0 => "ready".to_string(),
+            1 => "ready next turn".to_string(),
+            turns => format!("ready in {} turns", turns),
+        };
+        ui.show(&format!("{} - {} ({})", skill.name, skill.description, ready));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Weapon;
+
+    fn skill(classes: Vec<Class>, weapons: Vec<&str>, cooldown: i32) -> Skill {
+        Skill {
+            name: "Test".to_string(),
+            description: String::new(),
+            effect: SkillEffect::Taunt { turns: 1 },
+            cooldown,
+            classes,
+            weapons: weapons.into_iter().map(str::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn skills_need_the_class_and_the_weapon() {
+        let mut player = Player::new("Tester".to_string());
+        assert!(skill(Vec::new(), Vec::new(), 0).usable_by(&player));
+        assert!(!skill(vec![Class::Mage], Vec::new(), 0).usable_by(&player));
+        assert!(!skill(vec![Class::Warrior], vec!["Sword"], 0).usable_by(&player));
+        player.equippedWeapon = Weapon { name: "Sword".to_string(), ..Weapon::fist() };
+        assert!(skill(vec![Class::Warrior], vec!["Sword"], 0).usable_by(&player));
+    }
+
+    #[test]
+    fn cooldowns_count_down_each_turn() {
+        let skill = skill(Vec::new(), Vec::new(), 2);
+        let mut cooldowns = Cooldowns::default();
+        cooldowns.start(&skill);
+        assert_eq!(cooldowns.remaining(&skill), 2);
+        cooldowns.tick();
+        assert_eq!(cooldowns.remaining(&skill), 1);
+        cooldowns.tick();
+        assert_eq!(cooldowns.remaining(&skill), 0);
+    }
+}
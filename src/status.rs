@@ -0,0 +1,198 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::Frontend;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum StatusKind {
+    // Lose potency hp every turn, stacks by adding potency
+    Poison,
+    // Lose potency hp every turn
+    Burn,
+    // Can't act until it wears off
+    Freeze,
+    // Loses their next turn
+    Stun,
+    // Attacks lose potency power
+    Slow,
+    // Gain potency hp every turn
+    Regen,
+    // Does nothing but attack
+    Taunt,
+}
+
+impl StatusKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusKind::Poison => "poisoned",
+            StatusKind::Burn => "burning",
+            StatusKind::Freeze => "frozen",
+            StatusKind::Stun => "stunned",
+            StatusKind::Slow => "slowed",
+            StatusKind::Regen => "regenerating",
+            StatusKind::Taunt => "taunted",
+        }
+    }
+
+    pub fn harmful(&self) -> bool {
+        *self != StatusKind::Regen
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StatusApply {
+    // A status a spell, attack or item can inflict, as written in the content files
+    pub kind: StatusKind,
+    pub turns: i32,
+    #[serde(default)]
+    pub potency: i32,
+    // Percent chance to take hold, always for items
+    #[serde(default = "always")]
+    pub chance: u32,
+}
+
+fn always() -> u32 {
+    100
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Status {
+    pub kind: StatusKind,
+    pub turnsLeft: i32,
+    pub potency: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Statuses(Vec<Status>);
+
+impl Statuses {
+    // Adds a status following the stacking rules: poison adds its potency to what is
+    // already there, everything else keeps the stronger potency. Either way the
+    // longer duration wins. Returns false if the target is immune.
+    pub fn apply(&mut self, apply: &StatusApply, immunities: &[StatusKind]) -> bool {
+        if immunities.contains(&apply.kind) {
+            return false;
+        }
+        match self.0.iter_mut().find(|s| s.kind == apply.kind) {
+            Some(status) => {
+                status.turnsLeft = status.turnsLeft.max(apply.turns);
+                status.potency = if apply.kind == StatusKind::Poison {
+                    status.potency + apply.potency
+                } else {
+                    status.potency.max(apply.potency)
+                };
+            }
+            None => self.0.push(Status { kind: apply.kind, turnsLeft: apply.turns, potency: apply.potency }),
+        }
+        true
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.0.iter().any(|s| s.kind == kind)
+    }
+
+    pub fn potency(&self, kind: StatusKind) -> i32 {
+        self.0.iter().filter(|s| s.kind == kind).map(|s| s.potency).sum()
+    }
+
+    // The status keeping its holder from acting this turn, if any
+    pub fn skips_turn(&self) -> Option<StatusKind> {
+        [StatusKind::Freeze, StatusKind::Stun].into_iter().find(|k| self.has(*k))
+    }
+
+    pub fn cure(&mut self, kinds: &[StatusKind]) {
+        self.0.retain(|s| !kinds.contains(&s.kind));
+    }
+
+    pub fn cure_harmful(&mut self) {
+        self.0.retain(|s| !s.kind.harmful());
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    // e.g. " (poisoned 2, slowed 1)", or nothing without statuses
+    pub fn describe(&self) -> String {
+        if self.0.is_empty() {
+            return String::new();
+        }
+        let statuses: Vec<String> = self.0.iter().map(|s| format!("{} {}", s.kind.name(), s.turnsLeft)).collect();
+        format!(" ({})", statuses.join(", "))
+    }
+}
+
+// Rolls a status's chance and applies it, telling the player what happened
+pub fn inflict(ui: &mut dyn Frontend, rng: &mut impl Rng, name: &str, statuses: &mut Statuses, apply: &StatusApply, immunities: &[StatusKind]) {
+    if rng.gen_range(0..100) >= apply.chance {
+        return;
+    }
+    if statuses.apply(apply, immunities) {
+        ui.show(&format!("{} is {}!", name, apply.kind.name()));
+    } else {
+        ui.show(&format!("{} is immune to being {}!", name, apply.kind.name()));
+    }
+}
+
+// The end of a combatant's turn: damage and healing over time, then every status counts down
+pub fn tick(ui: &mut dyn Frontend, name: &str, hp: &mut i32, maxHp: i32, statuses: &mut Statuses) {
+    for status in &mut statuses.0 {
+        match status.kind {
+            StatusKind::Poison | StatusKind::Burn if status.potency > 0 => {
+                *hp = (*hp - status.potency).max(0);
+                ui.show(&format!("{} takes {} damage from being {}! {} hp remaining!", name, status.potency, status.kind.name(), hp));
+            }
+            StatusKind::Regen if status.potency > 0 && *hp < maxHp => {
+                *hp = (*hp + status.potency).min(maxHp);
+                ui.show(&format!("{} regenerates {} hp! {} hp remaining!", name, status.potency, hp));
+            }
+            _ => (),
+        }
+        status.turnsLeft -= 1;
+        if status.turnsLeft <= 0 {
+            ui.show(&format!("{} is no longer {}!", name, status.kind.name()));
+        }
+    }
+    statuses.0.retain(|s| s.turnsLeft > 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::ScriptedFrontend;
+
+    fn apply(kind: StatusKind, turns: i32, potency: i32) -> StatusApply {
+        StatusApply { kind, turns, potency, chance: 100 }
+    }
+
+    #[test]
+    fn poison_stacks_and_other_statuses_refresh() {
+        let mut statuses = Statuses::default();
+        statuses.apply(&apply(StatusKind::Poison, 3, 1), &[]);
+        statuses.apply(&apply(StatusKind::Poison, 2, 2), &[]);
+        statuses.apply(&apply(StatusKind::Burn, 2, 4), &[]);
+        statuses.apply(&apply(StatusKind::Burn, 3, 1), &[]);
+        assert_eq!(statuses.potency(StatusKind::Poison), 3);
+        assert_eq!(statuses.potency(StatusKind::Burn), 4);
+        assert_eq!(statuses.describe(), " (poisoned 3, burning 3)");
+        assert!(!statuses.apply(&apply(StatusKind::Stun, 1, 0), &[StatusKind::Stun]));
+        assert_eq!(statuses.skips_turn(), None);
+    }
+
+    #[test]
+    fn ticks_deal_damage_heal_and_expire() {
+        let mut ui = ScriptedFrontend::new(&[]);
+        let mut statuses = Statuses::default();
+        statuses.apply(&apply(StatusKind::Poison, 1, 3), &[]);
+        statuses.apply(&apply(StatusKind::Regen, 2, 5), &[]);
+        let mut hp = 10;
+        tick(&mut ui, "Tester", &mut hp, 20, &mut statuses);
+        assert_eq!(hp, 12);
+        assert!(!statuses.has(StatusKind::Poison));
+        tick(&mut ui, "Tester", &mut hp, 15, &mut statuses);
+        assert_eq!(hp, 15);
+        assert_eq!(statuses.describe(), "");
+        assert!(ui.transcript().contains("Tester is no longer poisoned!"));
+    }
+}
@@ -0,0 +1,91 @@
+use std::fs;
+
+use zyveria::class::Class;
+use zyveria::player::Player;
+use zyveria::save::{self, SaveError, CURRENT_VERSION};
+
+fn fixture(name: &str) -> Player {
+    let data = fs::read_to_string(format!("tests/fixtures/saves/{}.json", name)).unwrap();
+    save::parse_save(&data).unwrap_or_else(|e| panic!("{} failed to load: {}", name, e))
+}
+
+// Every fixture is the same character, saved by each version of the game
+fn assert_veteran(player: &Player) {
+    assert_eq!(player.name, "Veteran");
+    assert_eq!((player.hp, player.maxHp, player.mp, player.maxMp), (80, 110, 3, 6));
+    assert_eq!((player.level, player.strength, player.defense), (1, 1, 2));
+    assert_eq!((player.exp, player.gold), (12, 37));
+    assert_eq!(player.equippedWeapon.name, "Sword");
+    assert_eq!(player.inventory.count("herb"), 3);
+    assert_eq!(player.inventory.count("mana_stone"), 1);
+    assert_eq!(player.spellInventory[0].name, "Firebolt");
+}
+
+#[test]
+fn version_1_string_inventory_loads() {
+    assert_veteran(&fixture("v1"));
+}
+
+#[test]
+fn version_1_stacked_inventory_loads() {
+    let player = fixture("v1_stacked");
+    assert_veteran(&player);
+    assert_eq!(player.seed, Some(1234));
+}
+
+#[test]
+fn version_2_loads() {
+    assert_veteran(&fixture("v2"));
+}
+
+#[test]
+fn version_3_loads() {
+    let player = fixture("v3");
+    assert_veteran(&player);
+    assert_eq!(player.class, Class::Warrior);
+}
+
+#[test]
+fn version_4_loads() {
+    let player = fixture("v4");
+    assert_veteran(&player);
+    assert_eq!((player.agility, player.intelligence, player.statPoints), (0, 0, 0));
+}
+
+#[test]
+fn version_5_loads() {
+    let player = fixture("v5");
+    assert_veteran(&player);
+    assert_eq!(player.shopStock["Shop"]["smoke_bomb"].sold, 3);
+    assert!(fixture("v4").shopStock.is_empty());
+}
+
+#[test]
+fn saves_without_hp_load_alive() {
+    let player = fixture("v2_dead");
+    assert_eq!((player.hp, player.maxHp), (1, 110));
+}
+
+#[test]
+fn older_saves_get_the_class_their_stats_lean_towards() {
+    let v2 = |strength: i32, defense: i32, mp: i32| {
+        format!(r#"{{"version":2,"player":{{"name":"Old","hp":1,"maxHp":100,"mp":0,"maxMp":{},"equippedWeapon":{{"name":"Fist","description":"","minDamage":1,"maxDamage":5}},"level":2,"strength":{},"defense":{},"exp":0,"gold":0,"inventory":{{}},"spellInventory":[]}}}}"#, mp, strength, defense)
+    };
+    assert_eq!(fixture("v2").class, Class::Warrior);
+    assert_eq!(save::parse_save(&v2(0, 2, 11)).unwrap().class, Class::Mage);
+    assert_eq!(save::parse_save(&v2(4, 2, 5)).unwrap().class, Class::Barbarian);
+    assert_eq!(save::parse_save(&v2(0, 0, 5)).unwrap().class, Class::Warrior);
+}
+
+#[test]
+fn there_is_a_fixture_for_every_version() {
+    for version in 2..=CURRENT_VERSION {
+        assert!(fs::metadata(format!("tests/fixtures/saves/v{}.json", version)).is_ok(), "missing fixture for version {}", version);
+    }
+}
+
+#[test]
+fn saves_from_newer_games_are_refused() {
+    let data = format!(r#"{{"version":{},"player":{{}}}}"#, CURRENT_VERSION + 1);
+    assert!(matches!(save::parse_save(&data), Err(SaveError::UnsupportedVersion { .. })));
+}
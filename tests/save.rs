@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::PathBuf;
+
+use zyveria::player::Player;
+use zyveria::save::{self, SaveError};
+
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("zyveria-save-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn missing_save_loads_as_none() {
+    let dir = temp_dir("missing");
+    assert!(Player::load(&dir, "Nobody").unwrap().is_none());
+}
+
+#[test]
+fn save_round_trips() {
+    let dir = temp_dir("round-trip");
+    let mut player = Player::new("Ayla".to_string());
+    player.gold = 42;
+    player.save(&dir).unwrap();
+    let loaded = Player::load(&dir, "Ayla").unwrap().unwrap();
+    assert_eq!(loaded.gold, 42);
+    assert!(!dir.join("Ayla.json.tmp").exists());
+}
+
+#[test]
+fn saves_are_written_at_the_current_version() {
+    let dir = temp_dir("version");
+    Player::new("Dara".to_string()).save(&dir).unwrap();
+    let saved: serde_json::Value = serde_json::from_str(&fs::read_to_string(save::save_path(&dir, "Dara")).unwrap()).unwrap();
+    assert_eq!(saved["version"], save::CURRENT_VERSION);
+    assert_eq!(saved["player"]["name"], "Dara");
+}
+
+#[test]
+fn saving_again_backs_up_the_previous_save() {
+    let dir = temp_dir("backup");
+    let mut player = Player::new("Bram".to_string());
+    player.gold = 1;
+    player.save(&dir).unwrap();
+    player.gold = 2;
+    player.save(&dir).unwrap();
+    let backup = save::parse_save(&fs::read_to_string(save::backup_path(&dir, "Bram")).unwrap()).unwrap();
+    assert_eq!(backup.gold, 1);
+    assert_eq!(Player::load(&dir, "Bram").unwrap().unwrap().gold, 2);
+}
+
+#[test]
+fn corrupt_save_is_an_error_not_a_fresh_start() {
+    let dir = temp_dir("corrupt");
+    fs::write(save::save_path(&dir, "Cyd"), "{ not json").unwrap();
+    match Player::load(&dir, "Cyd") {
+        Err(SaveError::Corrupt { .. }) => (),
+        other => panic!("expected a corrupt save error, got {:?}", other.map(|p| p.is_some())),
+    }
+    let moved = save::quarantine(&dir, "Cyd").unwrap();
+    assert!(moved.exists());
+    assert!(!save::save_path(&dir, "Cyd").exists());
+    fs::write(save::save_path(&dir, "Cyd"), "{ also not json").unwrap();
+    let second = save::quarantine(&dir, "Cyd").unwrap();
+    assert_ne!(moved, second);
+    assert_eq!(fs::read_to_string(&moved).unwrap(), "{ not json");
+}
+
+#[test]
+fn names_that_leave_the_save_directory_are_refused() {
+    assert!(save::sanitize_name("../x").is_err());
+    assert!(save::sanitize_name("a/b").is_err());
+    assert!(save::sanitize_name(".hidden").is_err());
+    assert!(save::sanitize_name("   ").is_err());
+    assert!(save::sanitize_name(&"a".repeat(25)).is_err());
+    assert!(save::sanitize_name("Delete").is_err());
+    assert!(save::sanitize_name("new character").is_err());
+    assert_eq!(save::sanitize_name("  Sir O'Malley-2 ").unwrap(), "Sir O'Malley-2");
+}
+
+#[test]
+fn saves_can_be_listed_copied_renamed_and_deleted() {
+    let dir = temp_dir("slots");
+    let mut player = Player::new("Brin".to_string());
+    player.level = 3;
+    player.gold = 12;
+    player.save(&dir).unwrap();
+    player.save(&dir).unwrap();
+    Player::new("Ayla".to_string()).save(&dir).unwrap();
+    fs::write(dir.join("Broken.json"), "{ not json").unwrap();
+
+    let names = |dir| save::list_saves(dir).into_iter().map(|s| s.name).collect::<Vec<_>>();
+    assert_eq!(names(&dir), ["Ayla", "Brin"]);
+    let brin = &save::list_saves(&dir)[1];
+    assert_eq!((brin.level, brin.gold), (3, 12));
+    assert!(brin.savedAt.is_some());
+
+    save::copy_save(&dir, "Brin", "Cato").unwrap();
+    assert_eq!(Player::load(&dir, "Cato").unwrap().unwrap().gold, 12);
+    save::rename_save(&dir, "Brin", "Dane").unwrap();
+    assert!(!dir.join("Brin.json").exists());
+    assert!(!dir.join("Brin.json.bak").exists());
+    save::delete_save(&dir, "Ayla").unwrap();
+    assert_eq!(names(&dir), ["Cato", "Dane"]);
+}
+
+#[test]
+fn timestamps_format_as_utc_dates() {
+    assert_eq!(save::format_timestamp(0), "1970-01-01 00:00");
+    assert_eq!(save::format_timestamp(951_782_400 + 3_661), "2000-02-29 01:01");
+}
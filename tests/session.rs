@@ -0,0 +1,417 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use zyveria::combat::{Action, EnemyAction};
+use zyveria::content::{Content, Encounter, StatRange};
+use zyveria::frontend::ScriptedFrontend;
+use zyveria::game::{play, Game};
+use zyveria::player::Player;
+
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("zyveria-session-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn session_in(dir: PathBuf, seed: u64, inputs: &[&str]) -> String {
+    let content = Content::load(Path::new("content")).expect("bundled content is valid");
+    session_with(&content, dir, seed, inputs)
+}
+
+fn session_with(content: &Content, dir: PathBuf, seed: u64, inputs: &[&str]) -> String {
+    let mut game = Game::new(Some(seed));
+    game.oldSaveDir = dir.join("old");
+    game.saveDir = dir;
+    let mut ui = ScriptedFrontend::new(inputs);
+    play(&mut ui, content, &mut game);
+    ui.transcript()
+}
+
+fn session(seed: u64, inputs: &[&str]) -> String {
+    session_in(temp_dir(&inputs[0].replace(' ', "-")), seed, inputs)
+}
+
+#[test]
+fn new_player_can_look_around_and_quit() {
+    let transcript = session(1, &["Scripted Newcomer", "warrior", "status", "inventory", "quit"]);
+    assert!(transcript.contains("Creating a new profile for Scripted Newcomer."));
+    assert!(transcript.contains("Scripted Newcomer, you are a Level 0 Warrior"));
+    assert!(transcript.contains("Carrying 2/40 items"));
+}
+
+#[test]
+fn shops_refuse_a_player_without_gold() {
+    let transcript = session(1, &["Scripted Pauper", "warrior", "smithy", "sword", "shop", "herb", "quit"]);
+    assert!(transcript.contains("You do not have enough gold to buy Sword!"));
+    assert!(transcript.contains("You do not have enough gold to buy Herb!"));
+}
+
+#[test]
+fn seeded_fights_play_out_the_same() {
+    let mut inputs = vec!["Scripted Fighter", "warrior", "look", "plains"];
+    inputs.extend(["attack"; 20]);
+    let first = session(7, &inputs);
+    let second = session(7, &inputs);
+    assert!(first.contains("You have encountered a"));
+    assert!(first.contains("You have defeated the"));
+    assert_eq!(first, second);
+}
+
+#[test]
+fn running_out_of_input_ends_the_session() {
+    let transcript = session(1, &["Scripted Quitter", "warrior", "look", "forest"]);
+    assert!(transcript.contains("You have encountered a"));
+}
+
+#[test]
+fn corrupt_save_asks_before_starting_over() {
+    let dir = temp_dir("corrupt");
+    fs::write(dir.join("Scripted Corrupt.json"), "{ not json").unwrap();
+    let transcript = session_in(dir.clone(), 1, &["Scripted Corrupt", "no"]);
+    assert!(transcript.contains("Your save could not be loaded"));
+    assert!(dir.join("Scripted Corrupt.json").exists());
+
+    let transcript = session_in(dir.clone(), 1, &["Scripted Corrupt", "yes", "warrior", "save", "quit"]);
+    assert!(transcript.contains("Creating a new profile for Scripted Corrupt."));
+    assert!(transcript.contains("Game saved successfully!"));
+    assert_eq!(fs::read_to_string(dir.join("Scripted Corrupt.json.corrupt")).unwrap(), "{ not json");
+}
+
+#[test]
+fn existing_characters_are_listed_at_startup() {
+    let dir = temp_dir("menu");
+    session_in(dir.clone(), 1, &["Scripted Veteran", "warrior", "save", "quit"]);
+    let transcript = session_in(dir.clone(), 1, &["duplicate", "scripted veteran", "Scripted Twin", "scripted twin", "quit"]);
+    assert!(transcript.contains("> Scripted Veteran - Level 0, 0 gold, last played"));
+    assert!(transcript.contains("Scripted Veteran has been copied to Scripted Twin."));
+    assert!(transcript.contains("Game loaded successfully!"));
+    assert!(dir.join("Scripted Twin.json").exists());
+}
+
+#[test]
+fn quitting_and_fighting_save_without_being_asked() {
+    let dir = temp_dir("autosave");
+    session_in(dir.clone(), 1, &["Scripted Forgetful", "warrior", "quit"]);
+    assert!(dir.join("Scripted Forgetful.json").exists());
+
+    let mut inputs = vec!["scripted forgetful", "look", "plains"];
+    inputs.extend(["attack"; 20]);
+    let transcript = session_in(dir.clone(), 7, &inputs);
+    assert!(transcript.contains("You have defeated the"));
+    let saved = Player::load(&dir, "Scripted Forgetful").unwrap().unwrap();
+    assert!(saved.exp > 0);
+}
+
+// Seed 3 loses to the troll while punching it
+fn losing_fight() -> Vec<&'static str> {
+    let mut inputs = vec!["Scripted Loser", "look", "mountains"];
+    inputs.extend(["attack"; 60]);
+    inputs
+}
+
+#[test]
+fn defeat_costs_gold_and_respawns_at_the_shrine() {
+    let dir = temp_dir("defeat");
+    let mut player = Player::new("Scripted Loser".to_string());
+    player.gold = 100;
+    player.save(&dir).unwrap();
+    let transcript = session_in(dir.clone(), 3, &losing_fight());
+    assert!(transcript.contains("You have been defeated by the"));
+    assert!(transcript.contains("Scripted Loser wakes up at the shrine with 50 hp, 25 gold lighter."));
+    assert!(transcript.contains("Scripted Loser has 0 hp remaining!"));
+    let saved = Player::load(&dir, "Scripted Loser").unwrap().unwrap();
+    assert_eq!((saved.hp, saved.gold), (50, 75));
+}
+
+#[test]
+fn permadeath_deletes_the_save() {
+    let dir = temp_dir("permadeath");
+    let mut content = Content::load(Path::new("content")).expect("bundled content is valid");
+    content.rules.defeat.permadeath = true;
+    Player::new("Scripted Loser".to_string()).save(&dir).unwrap();
+    let transcript = session_with(&content, dir.clone(), 3, &losing_fight());
+    assert!(transcript.contains("GAME OVER"));
+    assert!(!dir.join("Scripted Loser.json").exists());
+}
+
+#[test]
+fn the_inn_restores_hp_and_mp_for_gold() {
+    let dir = temp_dir("inn");
+    let mut player = Player::new("Scripted Sleeper".to_string());
+    player.hp = 10;
+    player.mp = 0;
+    player.gold = 20;
+    player.save(&dir).unwrap();
+    let transcript = session_in(dir.clone(), 1, &["scripted sleeper", "inn", "rest", "inn", "rest", "quit"]);
+    assert!(transcript.contains("Scripted Sleeper has rested at the inn for 15 gold!"));
+    assert!(transcript.contains("You are already fully rested!"));
+    let saved = Player::load(&dir, "Scripted Sleeper").unwrap().unwrap();
+    assert_eq!((saved.hp, saved.mp, saved.gold), (100, 5, 5));
+}
+
+#[test]
+fn classes_limit_the_shrine_and_the_shops() {
+    let dir = temp_dir("classes");
+    let transcript = session_in(dir.clone(), 1, &["Scripted Brute", "barbarian", "shrine", "back", "quit"]);
+    assert!(transcript.contains("Scripted Brute sets out as a Barbarian!"));
+    assert!(transcript.contains("> Level Up as a Barbarian"));
+    assert!(!transcript.contains("> Level Up as a Mage"));
+
+    let mut player = Player::load(&dir, "Scripted Brute").unwrap().unwrap();
+    player.gold = 100;
+    player.save(&dir).unwrap();
+    let transcript = session_in(dir.clone(), 1, &["scripted brute", "spell shop", "firebolt", "quit"]);
+    assert!(transcript.contains("A Barbarian can't learn Firebolt!"));
+    assert_eq!(Player::load(&dir, "Scripted Brute").unwrap().unwrap().gold, 100);
+}
+
+#[test]
+fn status_and_shrine_show_what_the_next_level_needs_and_gives() {
+    let transcript = session(1, &["Scripted Novice", "warrior", "status", "shrine", "warrior", "quit"]);
+    assert!(transcript.contains("Exp: 0/50 (50 to next level)"));
+    assert!(transcript.contains("Level 1: Strength 1 -> 2, Defense 2 -> 4, HP 110 -> 120, MP 3 -> 4"));
+    assert!(transcript.contains("You do not have enough exp to level up!"));
+}
+
+#[test]
+fn auto_level_spends_exp_after_a_fight() {
+    let dir = temp_dir("auto-level");
+    let mut content = Content::load(Path::new("content")).expect("bundled content is valid");
+    content.rules.leveling.autoLevel = true;
+    content.rules.leveling.levelCap = 1;
+    let mut player = Player::new("Scripted Climber".to_string());
+    player.exp = 200;
+    player.save(&dir).unwrap();
+    let mut inputs = vec!["scripted climber", "look", "plains"];
+    inputs.extend(["attack"; 40]);
+    let transcript = session_with(&content, dir.clone(), 7, &inputs);
+    assert!(transcript.contains("Scripted Climber has leveled up to level 1!"));
+    assert!(!transcript.contains("leveled up to level 2"));
+    let saved = Player::load(&dir, "Scripted Climber").unwrap().unwrap();
+    assert_eq!((saved.level, saved.maxHp), (1, 110));
+}
+
+#[test]
+fn stat_points_can_be_spent_and_taken_back() {
+    let dir = temp_dir("stat-points");
+    let mut player = Player::new("Scripted Theorist".to_string());
+    player.exp = 50;
+    player.gold = 60;
+    player.save(&dir).unwrap();
+    let inputs = [
+        "scripted theorist", "shrine", "points", "intelligence 2", "hp",
+        "shrine", "respec", "agility 3", "status", "quit",
+    ];
+    let transcript = session_in(dir.clone(), 1, &inputs);
+    assert!(transcript.contains("Scripted Theorist has leveled up to level 1 and has 3 stat points to spend!"));
+    assert!(transcript.contains("Scripted Theorist has put 2 points into intelligence!"));
+    assert!(transcript.contains("Scripted Theorist has 3 stat points to spend again!"));
+    let saved = Player::load(&dir, "Scripted Theorist").unwrap().unwrap();
+    assert_eq!((saved.level, saved.intelligence, saved.agility, saved.maxHp), (1, 0, 3, 100));
+    assert_eq!((saved.statPoints, saved.gold), (0, 10));
+}
+
+#[test]
+fn spells_tell_how_effective_they_are() {
+    let transcript = session(1, &["Scripted Pyromancer", "mage", "look", "mountains", "magic", "firebolt", "quit"]);
+    assert!(transcript.contains("is weak to fire. It's super effective!"));
+}
+
+#[test]
+fn fights_show_statuses_and_antidotes_wait_for_one() {
+    let dir = temp_dir("statuses");
+    let mut player = Player::new("Scripted Patient".to_string());
+    player.gold = 12;
+    player.save(&dir).unwrap();
+    let mut inputs = vec!["scripted patient", "shop", "antidote", "use", "antidote", "look", "plains"];
+    inputs.extend(["attack"; 20]);
+    let transcript = session_in(dir, 2, &inputs);
+    assert!(transcript.contains("Antidote can only be used in a fight!"));
+    assert!(transcript.contains("/100 hp | "));
+}
+
+#[test]
+fn fights_show_the_turn_order() {
+    let mut inputs = vec!["Scripted Snail", "mage", "look", "plains"];
+    inputs.extend(["attack"; 3]);
+    let transcript = session(4, &inputs);
+    assert!(transcript.contains("Next up: You, "));
+}
+
+#[test]
+fn enemies_call_allies_and_flee_without_rewards() {
+    let mut content = Content::load(Path::new("content")).expect("bundled content is valid");
+    let action = |action, weight, once| EnemyAction { action, weight, hpBelow: None, once };
+    for enemy in &mut content.enemies {
+        enemy.hp = StatRange { min: 500, max: 500 };
+        enemy.actions = if enemy.name == "Goblin" {
+            vec![action(Action::CallAlly("Bear".to_string()), 1000, true), action(Action::Flee, 1, false)]
+        } else {
+            vec![action(Action::Flee, 1, false)]
+        };
+    }
+    content.locations[0].enemies = vec![Encounter::Single("Goblin".to_string())];
+    let mut inputs = vec!["Scripted Abandoned", "warrior", "look", "plains"];
+    inputs.extend(["attack"; 10]);
+    let transcript = session_with(&content, temp_dir("abandoned"), 1, &inputs);
+    assert!(transcript.contains("calls for help!"));
+    assert!(transcript.contains("joins the fight with 500 hp!"));
+    assert_eq!(transcript.matches("flees from the fight!").count(), 2);
+    assert!(!transcript.contains("You have defeated the"));
+}
+
+#[test]
+fn area_spells_hit_the_whole_group_and_rewards_add_up() {
+    let dir = temp_dir("group");
+    let mut content = Content::load(Path::new("content")).expect("bundled content is valid");
+    let goblin = content.enemies.iter_mut().find(|e| e.name == "Goblin").unwrap();
+    goblin.hp = StatRange { min: 10, max: 10 };
+    goblin.actions = Vec::new();
+    content.locations[0].enemies = vec![Encounter::Group(vec!["Goblin".to_string(), "Goblin".to_string()])];
+    let mut player = Player::new("Scripted Sorcerer".to_string());
+    player.intelligence = 10;
+    player.spellInventory.push(content.spell("Blizzard").unwrap().clone());
+    player.save(&dir).unwrap();
+    let inputs = ["Scripted Sorcerer", "look", "plains", "attack", "3", "magic", "blizzard", "quit"];
+    let transcript = session_with(&content, dir, 1, &inputs);
+    assert_eq!(transcript.matches("You have encountered a").count(), 2);
+    assert!(transcript.contains("Which enemy?"));
+    assert!(transcript.contains("Invalid target!"));
+    assert_eq!(transcript.matches("You have cast Blizzard on the").count(), 2);
+    assert_eq!(transcript.matches("has been defeated!").count(), 2);
+    assert!(transcript.contains("You have gained 10 exp!"));
+}
+
+#[test]
+fn warriors_defend_and_use_skills_on_cooldown() {
+    let mut content = Content::load(Path::new("content")).expect("bundled content is valid");
+    content.enemies.iter_mut().find(|e| e.name == "Bear").unwrap().hp = StatRange { min: 500, max: 500 };
+    content.locations[1].enemies = vec![Encounter::Single("Bear".to_string())];
+    let inputs = ["Scripted Duelist", "warrior", "look", "forest", "skill", "taunt", "skill", "taunt", "defend", "skill", "double strike", "quit"];
+    let transcript = session_with(&content, temp_dir("duelist"), 1, &inputs);
+    assert!(transcript.contains("Double Strike - Two quick cuts in one turn. (ready)"));
+    assert!(transcript.contains("You use Taunt!"));
+    assert!(transcript.contains("is taunted!"));
+    assert!(transcript.contains("Taunt is not ready yet!"));
+    assert!(transcript.contains("You raise your guard against the next hit!"));
+    assert!(transcript.contains("You use Double Strike!"));
+    assert_eq!(transcript.matches("You have dealt").count(), 2);
+}
+
+#[test]
+fn sold_out_stock_stays_sold_out_after_quitting() {
+    let dir = temp_dir("hoarder");
+    let mut player = Player::new("Scripted Hoarder".to_string());
+    player.gold = 100;
+    player.save(&dir).unwrap();
+    let first = session_in(dir.clone(), 1, &["Scripted Hoarder", "shop", "smoke bomb", "shop", "smoke bomb", "shop", "smoke bomb", "quit"]);
+    assert_eq!(first.matches("has bought Smoke Bomb").count(), 3);
+    let second = session_in(dir, 1, &["Scripted Hoarder", "shop", "smoke bomb", "quit"]);
+    assert!(second.contains("Smoke Bomb - Sold out"));
+    assert!(second.contains("Smoke Bomb is sold out!"));
+}
+
+#[test]
+fn saves_left_in_the_old_location_are_imported() {
+    let dir = temp_dir("import");
+    let old = dir.join("old");
+    fs::create_dir_all(&old).unwrap();
+    fs::copy("tests/fixtures/saves/v1.json", old.join("Veteran.json")).unwrap();
+    let transcript = session_in(dir.clone(), 1, &["yes", "Veteran", "status", "quit"]);
+    assert!(transcript.contains("Found characters saved by an older version in"));
+    assert!(transcript.contains("Veteran has been imported."));
+    assert!(transcript.contains("> Veteran - Level 1, 37 gold"));
+    assert!(transcript.contains("Game loaded successfully!"));
+    assert!(!old.join("Veteran.json").exists());
+    assert_eq!(Player::load(&dir, "Veteran").unwrap().unwrap().gold, 37);
+}
+
+#[test]
+fn declined_imports_leave_old_saves_alone() {
+    let dir = temp_dir("no-import");
+    let old = dir.join("old");
+    fs::create_dir_all(&old).unwrap();
+    fs::copy("tests/fixtures/saves/v2.json", old.join("Veteran.json")).unwrap();
+    let transcript = session_in(dir.clone(), 1, &["no", "quit"]);
+    assert!(transcript.contains(": Veteran"));
+    assert!(transcript.contains("They have been left where they are and won't be offered again."));
+    assert!(old.join("Veteran.json").exists());
+    let transcript = session_in(dir, 1, &["quit"]);
+    assert!(!transcript.contains("Found characters saved by an older version"));
+}
+
+#[test]
+fn characters_named_like_commands_still_load() {
+    let dir = temp_dir("reserved");
+    let mut player = Player::new("Delete".to_string());
+    player.gold = 9;
+    player.save(&dir).unwrap();
+    let transcript = session_in(dir, 1, &["delete", "status", "quit"]);
+    assert!(transcript.contains("Game loaded successfully!"));
+    assert!(!transcript.contains("Which character would you like to delete?"));
+}
+
+#[test]
+fn spell_list_shows_spells_as_they_will_be_cast() {
+    let dir = temp_dir("scholar");
+    let content = Content::load(Path::new("content")).expect("bundled content is valid");
+    let mut player = Player::new("Scripted Scholar".to_string());
+    player.intelligence = 4;
+    let mut stale = content.spell("Firebolt").unwrap().clone();
+    stale.mpCost = 9;
+    stale.damage = 1;
+    stale.element = None;
+    player.spellInventory.push(stale);
+    player.save(&dir).unwrap();
+    let transcript = session_with(&content, dir, 1, &["Scripted Scholar", "spells", "quit"]);
+    assert!(transcript.contains("Firebolt - A bolt of fire. - MP Cost: 1 - Damage: 14 fire"));
+}
+
+#[test]
+fn charging_powers_up_the_next_attack() {
+    let mut content = Content::load(Path::new("content")).expect("bundled content is valid");
+    content.enemies.iter_mut().find(|e| e.name == "Bear").unwrap().hp = StatRange { min: 500, max: 500 };
+    content.locations[1].enemies = vec![Encounter::Single("Bear".to_string())];
+    let inputs = ["Scripted Charger", "warrior", "look", "forest", "charge", "attack", "attack", "quit"];
+    let transcript = session_with(&content, temp_dir("charger"), 1, &inputs);
+    assert!(transcript.contains("You gather your strength for the next attack!"));
+    assert!(transcript.contains("[charged]"));
+    assert_eq!(transcript.matches("You unleash your charged attack!").count(), 1);
+    assert_eq!(transcript.matches("You have dealt").count(), 2);
+}
+
+#[test]
+fn bundled_content_plays_without_a_content_directory() {
+    let content = Content::bundled().expect("bundled content is valid");
+    assert_eq!(content.enemies.len(), Content::load(Path::new("content")).unwrap().enemies.len());
+    let transcript = session_with(&content, temp_dir("bundled"), 1, &["Scripted Traveller", "warrior", "status", "quit"]);
+    assert!(transcript.contains("Scripted Traveller, you are a Level 0 Warrior"));
+}
+
+#[test]
+fn corrupt_save_can_be_restored_from_its_backup() {
+    let dir = temp_dir("restore");
+    let mut player = Player::new("Scripted Careful".to_string());
+    player.gold = 55;
+    player.save(&dir).unwrap();
+    player.save(&dir).unwrap();
+    fs::write(dir.join("Scripted Careful.json"), "{ not json").unwrap();
+    let transcript = session_in(dir.clone(), 1, &["Scripted Careful", "yes", "status", "quit"]);
+    assert!(transcript.contains("The backup has been restored."));
+    assert_eq!(Player::load(&dir, "Scripted Careful").unwrap().unwrap().gold, 55);
+    assert_eq!(fs::read_to_string(dir.join("Scripted Careful.json.corrupt")).unwrap(), "{ not json");
+}
+
+#[test]
+fn starting_over_keeps_the_old_backup() {
+    let dir = temp_dir("start-over");
+    let mut player = Player::new("Scripted Restart".to_string());
+    player.gold = 55;
+    player.save(&dir).unwrap();
+    player.save(&dir).unwrap();
+    let backup = fs::read_to_string(dir.join("Scripted Restart.json.bak")).unwrap();
+    fs::write(dir.join("Scripted Restart.json"), "{ not json").unwrap();
+    session_in(dir.clone(), 1, &["Scripted Restart", "no", "yes", "warrior", "save", "save", "quit"]);
+    assert_eq!(fs::read_to_string(dir.join("Scripted Restart.json.bak.kept")).unwrap(), backup);
+}